@@ -0,0 +1,502 @@
+//! Derive macros for the [`cbqn`](https://docs.rs/cbqn) crate
+//!
+//! This crate provides `#[derive(ToBQN)]` and `#[derive(FromBQN)]`. Enable the `derive` feature
+//! of `cbqn` and use them through `cbqn::{FromBQN, ToBQN}` instead of depending on this crate
+//! directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Fields, Generics, Ident, LitStr, Member,
+    Path,
+};
+
+/// Derives `cbqn::ToBQN`
+///
+/// See the documentation of `cbqn::ToBQN` for the representation of the derived types and the
+/// supported `#[bqn(...)]` attributes.
+#[proc_macro_derive(ToBQN, attributes(bqn))]
+pub fn derive_to_bqn(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_bqn(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `cbqn::FromBQN`
+///
+/// See the documentation of `cbqn::FromBQN` for the conversion, and `cbqn::ToBQN` for the
+/// representation of the derived types and the supported `#[bqn(...)]` attributes.
+#[proc_macro_derive(FromBQN, attributes(bqn))]
+pub fn derive_from_bqn(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_bqn(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// How the fields of a struct or an enum variant are represented in BQN
+#[derive(Clone, Copy, PartialEq)]
+enum Repr {
+    Namespace,
+    List,
+    /// Newtype structs and variants are represented as their only field
+    Transparent,
+    /// Unit structs are represented as `@` and unit variants as strings
+    Unit,
+}
+
+struct Field {
+    member: Member,
+    /// Name of the field in Rust, used in error paths
+    label: String,
+    /// Normalized name of the field in BQN
+    name: String,
+    skip: bool,
+}
+
+struct Variant {
+    ident: Ident,
+    /// Normalized name of the variant, used to match variants from BQN
+    name: String,
+    /// Name of the variant as written, used as the value of unit variants
+    label: String,
+    repr: Repr,
+    fields: Vec<Field>,
+}
+
+#[derive(Default)]
+struct Attrs {
+    list: bool,
+    namespace: bool,
+    skip: bool,
+    rename: Option<LitStr>,
+}
+
+fn parse_attrs(attrs: &[syn::Attribute]) -> syn::Result<Attrs> {
+    let mut out = Attrs::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("bqn")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("list") {
+                out.list = true;
+            } else if meta.path.is_ident("namespace") {
+                out.namespace = true;
+            } else if meta.path.is_ident("skip") {
+                out.skip = true;
+            } else if meta.path.is_ident("rename") {
+                out.rename = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("unknown bqn attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    if out.list && out.namespace {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`list` and `namespace` can't be used together",
+        ));
+    }
+    Ok(out)
+}
+
+fn reject(attrs: &Attrs, span: Span, what: &str, allowed: &[&str]) -> syn::Result<()> {
+    let used = [
+        ("list", attrs.list),
+        ("namespace", attrs.namespace),
+        ("skip", attrs.skip),
+        ("rename", attrs.rename.is_some()),
+    ];
+    match used.iter().find(|(n, u)| *u && !allowed.contains(n)) {
+        Some((n, _)) => Err(syn::Error::new(
+            span,
+            format!("`{n}` can't be used on {what}"),
+        )),
+        None => Ok(()),
+    }
+}
+
+// Mirrors the normalization BQN does for namespace field names: underscores are removed and the
+// name is converted to lowercase.
+fn normalize(name: &str, span: Span) -> syn::Result<String> {
+    let name = name.strip_prefix("r#").unwrap_or(name);
+    let normalized = name
+        .chars()
+        .filter(|&c| c != '_')
+        .map(|c| c.to_ascii_lowercase())
+        .collect::<String>();
+    let mut chars = normalized.chars();
+    if chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    {
+        Ok(normalized)
+    } else {
+        Err(syn::Error::new(
+            span,
+            format!("`{name}` isn't a valid BQN namespace field name"),
+        ))
+    }
+}
+
+fn parse_fields(fields: &Fields, repr: Repr) -> syn::Result<Vec<Field>> {
+    let mut out: Vec<Field> = Vec::new();
+    for (i, f) in fields.iter().enumerate() {
+        let attrs = parse_attrs(&f.attrs)?;
+        let span = f.ident.as_ref().map_or_else(Span::call_site, Ident::span);
+        reject(&attrs, span, "fields", &["skip", "rename"])?;
+        let (member, label) = match &f.ident {
+            Some(ident) => (Member::from(ident.clone()), ident.to_string()),
+            None => (Member::from(i), i.to_string()),
+        };
+        let label = label.strip_prefix("r#").unwrap_or(&label).to_owned();
+        let name = match (&attrs.rename, repr) {
+            (Some(lit), Repr::Namespace) => normalize(&lit.value(), lit.span())?,
+            (Some(lit), _) => {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "`rename` can only be used on fields represented as a namespace",
+                ))
+            }
+            (None, Repr::Namespace) if !attrs.skip => normalize(&label, span)?,
+            (None, _) => label.clone(),
+        };
+        if repr == Repr::Namespace && !attrs.skip && out.iter().any(|o| !o.skip && o.name == name) {
+            return Err(syn::Error::new(
+                span,
+                format!("duplicate namespace field `{name}`"),
+            ));
+        }
+        out.push(Field {
+            member,
+            label,
+            name,
+            skip: attrs.skip,
+        });
+    }
+    if repr == Repr::Transparent && out[0].skip {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "the only field of a newtype can't be skipped",
+        ));
+    }
+    Ok(out)
+}
+
+fn fields_repr(fields: &Fields, attrs: &Attrs, span: Span) -> syn::Result<Repr> {
+    Ok(match fields {
+        Fields::Named(_) if attrs.list => Repr::List,
+        Fields::Named(_) => Repr::Namespace,
+        Fields::Unnamed(_) if attrs.namespace => {
+            return Err(syn::Error::new(
+                span,
+                "`namespace` can only be used with named fields",
+            ))
+        }
+        Fields::Unnamed(f) if f.unnamed.len() == 1 && !attrs.list => Repr::Transparent,
+        Fields::Unnamed(_) => Repr::List,
+        Fields::Unit if attrs.list || attrs.namespace => {
+            return Err(syn::Error::new(
+                span,
+                "`list` and `namespace` can't be used without fields",
+            ))
+        }
+        Fields::Unit => Repr::Unit,
+    })
+}
+
+fn parse_variants(input: &DeriveInput, data: &syn::DataEnum) -> syn::Result<Vec<Variant>> {
+    let container = parse_attrs(&input.attrs)?;
+    reject(&container, input.ident.span(), "enums", &["list"])?;
+    let mut out: Vec<Variant> = Vec::new();
+    for v in &data.variants {
+        let mut attrs = parse_attrs(&v.attrs)?;
+        reject(
+            &attrs,
+            v.ident.span(),
+            "variants",
+            &["list", "namespace", "rename"],
+        )?;
+        // `list` on an enum applies to its struct variants
+        if container.list && !attrs.namespace && matches!(v.fields, Fields::Named(_)) {
+            attrs.list = true;
+        }
+        let label = match &attrs.rename {
+            Some(lit) => lit.value(),
+            None => v.ident.to_string(),
+        };
+        let span = attrs.rename.as_ref().map_or(v.ident.span(), LitStr::span);
+        let name = normalize(&label, span)?;
+        if out.iter().any(|o| o.name == name) {
+            return Err(syn::Error::new(
+                v.ident.span(),
+                format!("duplicate variant name `{name}`"),
+            ));
+        }
+        let repr = fields_repr(&v.fields, &attrs, v.ident.span())?;
+        let mut fields = parse_fields(&v.fields, repr)?;
+        // Error paths of variant fields go through the variant, as in `Shape.Circle.radius`
+        for f in &mut fields {
+            f.label = format!("{}.{}", v.ident, f.label);
+        }
+        out.push(Variant {
+            ident: v.ident.clone(),
+            name,
+            label,
+            repr,
+            fields,
+        });
+    }
+    Ok(out)
+}
+
+fn add_bounds(generics: &Generics, bound: Path) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+/// Returns an expression that converts the `fields` accessed through `access` into a
+/// `Result<BQNValue, cbqn::Error>`
+fn to_fields(
+    fields: &[Field],
+    repr: Repr,
+    ty: &str,
+    access: impl Fn(&Field) -> TokenStream2,
+) -> TokenStream2 {
+    let fields = fields.iter().filter(|f| !f.skip).collect::<Vec<_>>();
+    let converted = fields.iter().map(|f| {
+        let access = access(f);
+        let label = &f.label;
+        quote! {
+            ::cbqn::ToBQN::to_bqn(#access)
+                .map_err(|e| ::cbqn::__private::in_field(e, #ty, #label))?
+        }
+    });
+    match repr {
+        Repr::Namespace => {
+            let names = fields.iter().map(|f| &f.name);
+            quote! {
+                ::cbqn::__private::make_namespace(::std::vec![
+                    #((::std::string::String::from(#names), #converted)),*
+                ])
+            }
+        }
+        Repr::List => quote! {{
+            let elems: ::std::vec::Vec<::cbqn::BQNValue> = ::std::vec![#(#converted),*];
+            ::cbqn::BQNValue::try_from_values(elems)
+        }},
+        Repr::Transparent => {
+            let access = access(fields[0]);
+            quote! { ::cbqn::ToBQN::to_bqn(#access) }
+        }
+        Repr::Unit => quote! { ::cbqn::BQNValue::try_null() },
+    }
+}
+
+/// Returns an expression that converts `value` into `cons { fields }` as a `Result`
+///
+/// Errors about the fields are reported in `ty`, and errors about the value itself at `path`.
+fn from_fields(
+    fields: &[Field],
+    repr: Repr,
+    ty: &str,
+    path: &str,
+    cons: TokenStream2,
+    value: TokenStream2,
+) -> TokenStream2 {
+    let members = fields.iter().map(|f| &f.member);
+    let convert = |f: &Field, elem: TokenStream2| {
+        let label = &f.label;
+        quote! {
+            ::cbqn::FromBQN::from_bqn(#elem)
+                .map_err(|e| ::cbqn::__private::in_field(e, #ty, #label))?
+        }
+    };
+    match repr {
+        Repr::Namespace => {
+            let values = fields.iter().map(|f| {
+                if f.skip {
+                    return quote! { ::std::default::Default::default() };
+                }
+                let (label, name) = (&f.label, &f.name);
+                let value = convert(f, quote! { &v });
+                quote! {
+                    match ::cbqn::__private::take_field(&mut fields, #name) {
+                        ::std::option::Option::Some(v) => #value,
+                        ::std::option::Option::None => {
+                            return ::std::result::Result::Err(
+                                ::cbqn::__private::missing_field(#ty, #label, #name),
+                            )
+                        }
+                    }
+                }
+            });
+            quote! {{
+                let mut fields = ::cbqn::__private::namespace_fields(#value)
+                    .map_err(|e| ::cbqn::__private::in_type(e, #path))?;
+                ::std::result::Result::Ok(#cons { #(#members: #values),* })
+            }}
+        }
+        Repr::List => {
+            let len = fields.iter().filter(|f| !f.skip).count();
+            let mut i = 0usize;
+            let values = fields
+                .iter()
+                .map(|f| {
+                    if f.skip {
+                        return quote! { ::std::default::Default::default() };
+                    }
+                    let value = convert(f, quote! { &elems[#i] });
+                    i += 1;
+                    value
+                })
+                .collect::<Vec<_>>();
+            quote! {{
+                let elems = ::cbqn::__private::list_elements(#value, #path, #len)?;
+                ::std::result::Result::Ok(#cons { #(#members: #values),* })
+            }}
+        }
+        Repr::Transparent => {
+            let value = convert(&fields[0], value);
+            quote! { ::std::result::Result::Ok(#cons { 0: #value }) }
+        }
+        Repr::Unit => quote! {{
+            ::cbqn::__private::expect_null(#value, #path)?;
+            ::std::result::Result::Ok(#cons)
+        }},
+    }
+}
+
+fn expand_to_bqn(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let ty = ident.to_string();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let attrs = parse_attrs(&input.attrs)?;
+            reject(&attrs, ident.span(), "structs", &["list", "namespace"])?;
+            let repr = fields_repr(&data.fields, &attrs, ident.span())?;
+            let fields = parse_fields(&data.fields, repr)?;
+            to_fields(&fields, repr, &ty, |f| {
+                let member = &f.member;
+                quote! { &self.#member }
+            })
+        }
+        Data::Enum(data) => {
+            let arms = parse_variants(input, data)?.into_iter().map(|v| {
+                let (vident, name, label) = (&v.ident, &v.name, &v.label);
+                if v.repr == Repr::Unit {
+                    return quote! {
+                        Self::#vident => ::cbqn::BQNValue::try_from_str(#label),
+                    };
+                }
+                let bindings = v
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, _)| format_ident!("f{}", i))
+                    .collect::<Vec<_>>();
+                let members = v.fields.iter().map(|f| &f.member);
+                let data = to_fields(&v.fields, v.repr, &ty, |f| {
+                    let i = v.fields.iter().position(|o| o.member == f.member).unwrap();
+                    let binding = &bindings[i];
+                    quote! { #binding }
+                });
+                quote! {
+                    Self::#vident { #(#members: #bindings),* } => {
+                        let data = #data?;
+                        ::cbqn::__private::make_namespace(::std::vec![
+                            (::std::string::String::from(#name), data),
+                        ])
+                    }
+                }
+            });
+            quote! {
+                #[allow(unused_variables)]
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(u) => {
+            return Err(syn::Error::new(
+                u.union_token.span,
+                "ToBQN can't be derived for unions",
+            ))
+        }
+    };
+    let generics = add_bounds(&input.generics, parse_quote!(::cbqn::ToBQN));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::cbqn::ToBQN for #ident #ty_generics #where_clause {
+            fn to_bqn(&self) -> ::std::result::Result<::cbqn::BQNValue, ::cbqn::Error> {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_from_bqn(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let ty = ident.to_string();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let attrs = parse_attrs(&input.attrs)?;
+            reject(&attrs, ident.span(), "structs", &["list", "namespace"])?;
+            let repr = fields_repr(&data.fields, &attrs, ident.span())?;
+            let fields = parse_fields(&data.fields, repr)?;
+            from_fields(&fields, repr, &ty, &ty, quote! { Self }, quote! { value })
+        }
+        Data::Enum(data) => {
+            let arms = parse_variants(input, data)?.into_iter().map(|v| {
+                let (vident, name) = (&v.ident, &v.name);
+                if v.repr == Repr::Unit {
+                    return quote! {
+                        (#name, ::std::option::Option::None) => {
+                            ::std::result::Result::Ok(Self::#vident)
+                        }
+                    };
+                }
+                let path = format!("{ty}.{vident}");
+                let value = from_fields(
+                    &v.fields,
+                    v.repr,
+                    &ty,
+                    &path,
+                    quote! { Self::#vident },
+                    quote! { &data },
+                );
+                quote! {
+                    (#name, ::std::option::Option::Some(data)) => #value,
+                }
+            });
+            quote! {
+                let (variant, data) = ::cbqn::__private::enum_variant(value, #ty)?;
+                match (variant.as_str(), data) {
+                    #(#arms)*
+                    (variant, _) => ::std::result::Result::Err(
+                        ::cbqn::__private::unknown_variant(#ty, variant),
+                    ),
+                }
+            }
+        }
+        Data::Union(u) => {
+            return Err(syn::Error::new(
+                u.union_token.span,
+                "FromBQN can't be derived for unions",
+            ))
+        }
+    };
+    let generics = add_bounds(&input.generics, parse_quote!(::cbqn::FromBQN));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::cbqn::FromBQN for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn from_bqn(value: &::cbqn::BQNValue) -> ::std::result::Result<Self, ::cbqn::Error> {
+                #body
+            }
+        }
+    })
+}
@@ -1,18 +1,33 @@
+use crate::BQNError;
 use cbqn_sys as bindings;
 use thiserror::Error as ThisError;
 
 #[derive(ThisError, Debug)]
 pub enum Error {
+    #[error("BQN error: {0}")]
+    BQN(BQNError),
     #[error("CBQN error: {0}")]
     CBQN(String),
     #[error("Invalid type: {0}")]
     InvalidType(String),
+    #[error("Index out of bounds: {0}")]
+    IndexOutOfBounds(String),
     #[error("{0}")]
     NotSupported(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    // Resolves the line number of a compiler error in `source`
+    pub(crate) fn with_source(self, source: &str) -> Error {
+        match self {
+            Error::BQN(e) => Error::BQN(e.with_source(source)),
+            e => e,
+        }
+    }
+}
+
 pub use bindings::{
     BQNElType_elt_c16, BQNElType_elt_c32, BQNElType_elt_c8, BQNElType_elt_f64, BQNElType_elt_i16,
     BQNElType_elt_i32, BQNElType_elt_i8, BQNElType_elt_unk, BQNV,
@@ -24,28 +39,54 @@ mod native;
 #[cfg(feature = "native-backend")]
 mod eval {
     use super::*;
-    use crate::BQNValue;
-    use std::sync::OnceLock;
+    use crate::{BQNValue, Compiled};
 
-    static REBQN: OnceLock<BQNValue> = OnceLock::new();
+    // The functions below implement `eval`, so their code is evaluated directly with CBQN
+    static REBQN: Compiled = Compiled::with_eval(
+        r#"r←•ReBQN{repl⇐"none"}⋄{0‿(R𝕩)}⎊{𝕊: 1‿(•CurrentError@)}"#,
+        eval_unchecked,
+    );
+    static CALL1: Compiled =
+        Compiled::with_eval(r#"{0‿(𝕎𝕩)}⎊{𝕨𝕊𝕩: 1‿(•CurrentError@)}"#, eval_unchecked);
+    static CALL2: Compiled = Compiled::with_eval(
+        r#"{f‿w 𝕊 x: 0‿(w F x)}⎊{𝕨𝕊𝕩: 1‿(•CurrentError@)}"#,
+        eval_unchecked,
+    );
+
+    fn eval_unchecked(bqn: &str) -> Result<BQNValue> {
+        Ok(BQNValue::new(bqn_eval(BQNValue::try_from_str(bqn)?.value)?))
+    }
 
     pub fn backend_eval(bqn: &str) -> Result<BQNValue> {
-        let rebqn = REBQN.get_or_init(|| {
-            BQNValue::new(
-                bqn_eval(
-                    BQNValue::from(
-                        r#"r←•ReBQN{repl⇐"none"}⋄{0‿(R𝕩)}⎊{𝕊: 1‿("Error: "∾•CurrentError@)}"#,
-                    )
-                    .value,
-                )
-                .expect("ReBQN"),
-            )
-        });
-        let ret = rebqn.call1(&BQNValue::from(bqn))?;
+        let src = BQNValue::try_from_str(bqn)?;
+        result(BQNValue::new(bqn_call1(REBQN.get()?.value, src.value)?))
+            .map_err(|e| e.with_source(bqn))
+    }
+
+    pub fn backend_call1(f: &BQNValue, x: &BQNValue) -> Result<BQNValue> {
+        result(BQNValue::new(bqn_call2(
+            CALL1.get()?.value,
+            f.value,
+            x.value,
+        )?))
+    }
+
+    pub fn backend_call2(f: &BQNValue, w: &BQNValue, x: &BQNValue) -> Result<BQNValue> {
+        let fw = BQNValue::try_from_values(vec![f.try_clone()?, w.try_clone()?])?;
+        result(BQNValue::new(bqn_call2(
+            CALL2.get()?.value,
+            fw.value,
+            x.value,
+        )?))
+    }
+
+    // Unpacks the `⟨error, value⟩` pair returned by the evaluation functions above. The value of
+    // an error is the value that was thrown.
+    fn result(ret: BQNValue) -> Result<BQNValue> {
         let err = bqn_pick(ret.value, 0)?;
         if err != 0 {
             let error = BQNValue::new(bqn_pick(ret.value, 1)?);
-            Err(Error::CBQN(error.to_string()?))
+            Err(Error::BQN(BQNError::from_value(error)))
         } else {
             Ok(BQNValue::new(bqn_pick(ret.value, 1)?))
         }
@@ -53,7 +94,10 @@ mod eval {
 }
 
 #[cfg(feature = "native-backend")]
-pub use crate::backend::{eval::backend_eval, native::*};
+pub use crate::backend::{
+    eval::{backend_call1, backend_call2, backend_eval},
+    native::*,
+};
 
 #[cfg(feature = "wasi-backend")]
 mod wasi;
@@ -77,6 +77,31 @@ pub fn bqn_makeI8Vec(a: &[i8]) -> Result<BQNV> {
     Ok(unsafe { bindings::bqn_makeI8Vec(a.len().try_into().unwrap(), a.as_ptr()) })
 }
 
+#[cfg(feature = "ndarray")]
+pub fn bqn_makeF64Arr(shape: &[usize], a: &[f64]) -> Result<BQNV> {
+    Ok(unsafe { bindings::bqn_makeF64Arr(shape.len(), shape.as_ptr(), a.as_ptr()) })
+}
+
+#[cfg(feature = "ndarray")]
+pub fn bqn_makeI32Arr(shape: &[usize], a: &[i32]) -> Result<BQNV> {
+    Ok(unsafe { bindings::bqn_makeI32Arr(shape.len(), shape.as_ptr(), a.as_ptr()) })
+}
+
+#[cfg(feature = "ndarray")]
+pub fn bqn_makeI16Arr(shape: &[usize], a: &[i16]) -> Result<BQNV> {
+    Ok(unsafe { bindings::bqn_makeI16Arr(shape.len(), shape.as_ptr(), a.as_ptr()) })
+}
+
+#[cfg(feature = "ndarray")]
+pub fn bqn_makeI8Arr(shape: &[usize], a: &[i8]) -> Result<BQNV> {
+    Ok(unsafe { bindings::bqn_makeI8Arr(shape.len(), shape.as_ptr(), a.as_ptr()) })
+}
+
+#[cfg(feature = "ndarray")]
+pub fn bqn_makeC32Arr(shape: &[usize], a: &[u32]) -> Result<BQNV> {
+    Ok(unsafe { bindings::bqn_makeC32Arr(shape.len(), shape.as_ptr(), a.as_ptr()) })
+}
+
 pub fn bqn_makeObjVec(a: &[BQNV]) -> Result<BQNV> {
     Ok(unsafe { bindings::bqn_makeObjVec(a.len().try_into().unwrap(), a.as_ptr()) })
 }
@@ -105,6 +130,18 @@ pub fn bqn_readF64Arr(v: BQNV, buf: &mut [f64]) -> Result<()> {
     Ok(unsafe { bindings::bqn_readF64Arr(v, buf.as_mut_ptr()) })
 }
 
+pub fn bqn_readI32Arr(v: BQNV, buf: &mut [i32]) -> Result<()> {
+    Ok(unsafe { bindings::bqn_readI32Arr(v, buf.as_mut_ptr()) })
+}
+
+pub fn bqn_readI16Arr(v: BQNV, buf: &mut [i16]) -> Result<()> {
+    Ok(unsafe { bindings::bqn_readI16Arr(v, buf.as_mut_ptr()) })
+}
+
+pub fn bqn_readI8Arr(v: BQNV, buf: &mut [i8]) -> Result<()> {
+    Ok(unsafe { bindings::bqn_readI8Arr(v, buf.as_mut_ptr()) })
+}
+
 pub fn bqn_readObjArr(v: BQNV, buf: &mut [BQNV]) -> Result<()> {
     Ok(unsafe { bindings::bqn_readObjArr(v, buf.as_mut_ptr()) })
 }
@@ -4,14 +4,35 @@ use super::{
     bindings::{self, BQNV},
     Error, Result,
 };
-use crate::BQNValue;
-use std::{cell::UnsafeCell, io::Read, mem, num::TryFromIntError, sync::LazyLock};
+use crate::{BQNError, BQNValue};
+use parking_lot::Mutex;
+use std::{
+    cell::{Cell, UnsafeCell},
+    collections::HashMap,
+    io::Read,
+    mem,
+    num::TryFromIntError,
+    ptr,
+    sync::LazyLock,
+};
 use wasmer::*;
 use wasmer_wasix::{virtual_fs, Pipe, WasiEnv};
 
 #[inline]
 pub fn backend_eval(bqn: &str) -> Result<BQNValue> {
-    Ok(BQNValue::new(bqn_eval(BQNValue::from(bqn).value)?))
+    bqn_eval(BQNValue::from(bqn).value)
+        .map(BQNValue::new)
+        .map_err(|e| e.with_source(bqn))
+}
+
+#[inline]
+pub fn backend_call1(f: &BQNValue, x: &BQNValue) -> Result<BQNValue> {
+    Ok(BQNValue::new(bqn_call1(f.value, x.value)?))
+}
+
+#[inline]
+pub fn backend_call2(f: &BQNValue, w: &BQNValue, x: &BQNValue) -> Result<BQNValue> {
+    Ok(BQNValue::new(bqn_call2(f.value, w.value, x.value)?))
 }
 
 macro_rules! impl_error(($err:ty) => {
@@ -21,7 +42,11 @@ macro_rules! impl_error(($err:ty) => {
                 let _l = crate::LOCK.lock();
                 BQNFFI.stderr_unsafe()
             };
-            Error::CBQN(stderr)
+            if stderr.starts_with("Error: ") {
+                Error::BQN(BQNError::from_stderr(&stderr))
+            } else {
+                Error::CBQN(stderr)
+            }
         }
     }
 });
@@ -44,6 +69,8 @@ struct BqnFfi {
     bqn_hasField: TypedFunction<(BQNV, BQNV), i32>,
     bqn_eval: TypedFunction<BQNV, BQNV>,
     bqn_init: TypedFunction<(), ()>,
+    bqn_makeBoundFn1: TypedFunction<(u32, BQNV), BQNV>,
+    bqn_makeBoundFn2: TypedFunction<(u32, BQNV), BQNV>,
     bqn_makeChar: TypedFunction<u32, BQNV>,
     bqn_makeF64: TypedFunction<f64, BQNV>,
     bqn_makeF64Vec: TypedFunction<(u32, WasmPtr<u32>), BQNV>,
@@ -51,12 +78,25 @@ struct BqnFfi {
     bqn_makeI16Vec: TypedFunction<(u32, WasmPtr<u32>), BQNV>,
     bqn_makeI8Vec: TypedFunction<(u32, WasmPtr<u32>), BQNV>,
     bqn_makeObjVec: TypedFunction<(u32, WasmPtr<u32>), BQNV>,
+    #[cfg(feature = "ndarray")]
+    bqn_makeF64Arr: TypedFunction<(u32, WasmPtr<u32>, WasmPtr<u32>), BQNV>,
+    #[cfg(feature = "ndarray")]
+    bqn_makeI32Arr: TypedFunction<(u32, WasmPtr<u32>, WasmPtr<u32>), BQNV>,
+    #[cfg(feature = "ndarray")]
+    bqn_makeI16Arr: TypedFunction<(u32, WasmPtr<u32>, WasmPtr<u32>), BQNV>,
+    #[cfg(feature = "ndarray")]
+    bqn_makeI8Arr: TypedFunction<(u32, WasmPtr<u32>, WasmPtr<u32>), BQNV>,
+    #[cfg(feature = "ndarray")]
+    bqn_makeC32Arr: TypedFunction<(u32, WasmPtr<u32>, WasmPtr<u32>), BQNV>,
     bqn_makeUTF8Str: TypedFunction<(u32, WasmPtr<u32>), BQNV>,
     bqn_pick: TypedFunction<(BQNV, u32), BQNV>,
     bqn_readC32Arr: TypedFunction<(BQNV, WasmPtr<u32>), ()>,
     bqn_readChar: TypedFunction<BQNV, u32>,
     bqn_readF64: TypedFunction<BQNV, f64>,
     bqn_readF64Arr: TypedFunction<(BQNV, WasmPtr<u32>), ()>,
+    bqn_readI32Arr: TypedFunction<(BQNV, WasmPtr<u32>), ()>,
+    bqn_readI16Arr: TypedFunction<(BQNV, WasmPtr<u32>), ()>,
+    bqn_readI8Arr: TypedFunction<(BQNV, WasmPtr<u32>), ()>,
     bqn_readObjArr: TypedFunction<(BQNV, WasmPtr<u32>), ()>,
     bqn_type: TypedFunction<BQNV, i32>,
     bqn_rank: TypedFunction<BQNV, u32>,
@@ -65,6 +105,10 @@ struct BqnFfi {
     store: UnsafeCell<Store>,
     stderr: UnsafeCell<Pipe>,
     memory: Memory,
+    table: Option<Table>,
+    // Table indices of the host functions that are called by bound functions, keyed by the
+    // address of the Rust function
+    trampolines: Mutex<HashMap<usize, u32>>,
 }
 
 impl BqnFfi {
@@ -73,8 +117,17 @@ impl BqnFfi {
     /// However, in practice, the cbqn-sys wrapper to the C shared object is also not thread-safe and
     /// the implementation in cbqn-rs crate does locking in a way that this code cannot be
     /// multi-threaded in practice.
-    fn get_store_unsafe(&self) -> &mut Store {
-        unsafe { self.store.get().as_mut().unwrap() }
+    ///
+    /// While a bound function is running, the store is borrowed by the WASM call that called it,
+    /// so the store given to the bound function's host function is returned instead.
+    fn get_store_unsafe(&self) -> StoreMut<'_> {
+        let active = ACTIVE_STORE.with(Cell::get);
+        unsafe {
+            match active.as_mut() {
+                Some(store) => store.as_store_mut(),
+                None => self.store.get().as_mut().unwrap().as_store_mut(),
+            }
+        }
     }
 
     fn stderr_unsafe(&self) -> String {
@@ -84,6 +137,27 @@ impl BqnFfi {
         ret
     }
 }
+thread_local! {
+    // The store of the innermost host function that is calling a bound function
+    static ACTIVE_STORE: Cell<*mut StoreMut<'static>> = const { Cell::new(ptr::null_mut()) };
+}
+
+// Makes `get_store_unsafe` return `store` until the guard is dropped
+struct ActiveStore(*mut StoreMut<'static>);
+
+impl ActiveStore {
+    fn set(store: &mut StoreMut<'_>) -> ActiveStore {
+        let store = store as *mut StoreMut<'_> as *mut StoreMut<'static>;
+        ActiveStore(ACTIVE_STORE.with(|active| active.replace(store)))
+    }
+}
+
+impl Drop for ActiveStore {
+    fn drop(&mut self) {
+        ACTIVE_STORE.with(|active| active.set(self.0));
+    }
+}
+
 /// NOTE: The type is not really Sync. The user of this library must make sure this code is not
 /// going to be run in a multi-threaded context.
 unsafe impl Sync for BqnFfi {}
@@ -138,6 +212,8 @@ static BQNFFI: LazyLock<BqnFfi> = LazyLock::new(|| {
         bqn_hasField: wasmfn!(instance, store, "bqn_hasField"),
         bqn_eval: wasmfn!(instance, store, "bqn_eval"),
         bqn_init: wasmfn!(instance, store, "bqn_init"),
+        bqn_makeBoundFn1: wasmfn!(instance, store, "bqn_makeBoundFn1"),
+        bqn_makeBoundFn2: wasmfn!(instance, store, "bqn_makeBoundFn2"),
         bqn_makeChar: wasmfn!(instance, store, "bqn_makeChar"),
         bqn_makeF64: wasmfn!(instance, store, "bqn_makeF64"),
         bqn_makeF64Vec: wasmfn!(instance, store, "bqn_makeF64Vec"),
@@ -145,12 +221,25 @@ static BQNFFI: LazyLock<BqnFfi> = LazyLock::new(|| {
         bqn_makeI16Vec: wasmfn!(instance, store, "bqn_makeI16Vec"),
         bqn_makeI8Vec: wasmfn!(instance, store, "bqn_makeI8Vec"),
         bqn_makeObjVec: wasmfn!(instance, store, "bqn_makeObjVec"),
+        #[cfg(feature = "ndarray")]
+        bqn_makeF64Arr: wasmfn!(instance, store, "bqn_makeF64Arr"),
+        #[cfg(feature = "ndarray")]
+        bqn_makeI32Arr: wasmfn!(instance, store, "bqn_makeI32Arr"),
+        #[cfg(feature = "ndarray")]
+        bqn_makeI16Arr: wasmfn!(instance, store, "bqn_makeI16Arr"),
+        #[cfg(feature = "ndarray")]
+        bqn_makeI8Arr: wasmfn!(instance, store, "bqn_makeI8Arr"),
+        #[cfg(feature = "ndarray")]
+        bqn_makeC32Arr: wasmfn!(instance, store, "bqn_makeC32Arr"),
         bqn_makeUTF8Str: wasmfn!(instance, store, "bqn_makeUTF8Str"),
         bqn_pick: wasmfn!(instance, store, "bqn_pick"),
         bqn_readC32Arr: wasmfn!(instance, store, "bqn_readC32Arr"),
         bqn_readChar: wasmfn!(instance, store, "bqn_readChar"),
         bqn_readF64: wasmfn!(instance, store, "bqn_readF64"),
         bqn_readF64Arr: wasmfn!(instance, store, "bqn_readF64Arr"),
+        bqn_readI32Arr: wasmfn!(instance, store, "bqn_readI32Arr"),
+        bqn_readI16Arr: wasmfn!(instance, store, "bqn_readI16Arr"),
+        bqn_readI8Arr: wasmfn!(instance, store, "bqn_readI8Arr"),
         bqn_readObjArr: wasmfn!(instance, store, "bqn_readObjArr"),
         bqn_type: wasmfn!(instance, store, "bqn_type"),
         bqn_rank: wasmfn!(instance, store, "bqn_rank"),
@@ -163,106 +252,179 @@ static BQNFFI: LazyLock<BqnFfi> = LazyLock::new(|| {
             .get_memory("memory")
             .expect("Get WASM memory")
             .clone(),
+        table: instance
+            .exports
+            .get_table("__indirect_function_table")
+            .ok()
+            .cloned(),
+        trampolines: Mutex::new(HashMap::new()),
     }
 });
 
-fn with_buf<BT, T, F: FnMut(&[BT], &mut Store, WasmPtr<u32>) -> Result<T>>(
+// Returns the function table index of a host function calling `f`. CBQN calls bound functions
+// through function pointers, which in WASM are indices to the function table of the module.
+fn trampoline<F: Copy + Send + 'static>(
+    f: F,
+    key: usize,
+    new_fn: impl FnOnce(&mut StoreMut, &FunctionEnv<F>) -> Function,
+) -> Result<u32> {
+    let table = BQNFFI.table.as_ref().ok_or_else(|| {
+        Error::NotSupported(
+            "BoundFns require BQN.wasm to export a growable function table \
+             (link with -Wl,--export-table,--growable-table)"
+                .into(),
+        )
+    })?;
+
+    let mut trampolines = BQNFFI.trampolines.lock();
+    if let Some(idx) = trampolines.get(&key) {
+        return Ok(*idx);
+    }
+
+    let mut store = BQNFFI.get_store_unsafe();
+    let env = FunctionEnv::new(&mut store, f);
+    let func = new_fn(&mut store, &env);
+    let idx = table.grow(&mut store, 1, Value::FuncRef(Some(func)))?;
+    trampolines.insert(key, idx);
+    Ok(idx)
+}
+
+fn with_buf<BT, T, F: FnMut(&[BT], &mut StoreMut, WasmPtr<u32>) -> Result<T>>(
     buf: &[BT],
     mut f: F,
 ) -> Result<T> {
-    let store = BQNFFI.get_store_unsafe();
+    let mut store = BQNFFI.get_store_unsafe();
     let ptr = BQNFFI
         .malloc
-        .call(store, (buf.len() * mem::size_of::<BT>()).try_into()?)?;
+        .call(&mut store, (buf.len() * mem::size_of::<BT>()).try_into()?)?;
 
-    let ret = f(buf, store, ptr)?;
+    let ret = f(buf, &mut store, ptr)?;
 
-    BQNFFI.free.call(store, ptr)?;
+    BQNFFI.free.call(&mut store, ptr)?;
 
     Ok(ret)
 }
 
-fn with_buf_mut<BT, T, F: FnMut(&mut [BT], &mut Store, WasmPtr<u32>) -> Result<T>>(
+fn with_buf_mut<BT, T, F: FnMut(&mut [BT], &mut StoreMut, WasmPtr<u32>) -> Result<T>>(
     buf: &mut [BT],
     mut f: F,
 ) -> Result<T> {
-    let store = BQNFFI.get_store_unsafe();
+    let mut store = BQNFFI.get_store_unsafe();
     let ptr = BQNFFI
         .malloc
-        .call(store, (buf.len() * mem::size_of::<BT>()).try_into()?)?;
+        .call(&mut store, (buf.len() * mem::size_of::<BT>()).try_into()?)?;
 
-    let ret = f(buf, store, ptr)?;
+    let ret = f(buf, &mut store, ptr)?;
 
-    BQNFFI.free.call(store, ptr)?;
+    BQNFFI.free.call(&mut store, ptr)?;
 
     Ok(ret)
 }
 
 pub fn bqn_bound(v: BQNV) -> Result<u32> {
-    Ok(BQNFFI.bqn_bound.call(BQNFFI.get_store_unsafe(), v)?)
+    Ok(BQNFFI.bqn_bound.call(&mut BQNFFI.get_store_unsafe(), v)?)
 }
 
 pub fn bqn_call1(f: BQNV, x: BQNV) -> Result<BQNV> {
-    Ok(BQNFFI.bqn_call1.call(BQNFFI.get_store_unsafe(), f, x)?)
+    Ok(BQNFFI
+        .bqn_call1
+        .call(&mut BQNFFI.get_store_unsafe(), f, x)?)
 }
 
 pub fn bqn_call2(f: BQNV, w: BQNV, x: BQNV) -> Result<BQNV> {
-    Ok(BQNFFI.bqn_call2.call(BQNFFI.get_store_unsafe(), f, w, x)?)
+    Ok(BQNFFI
+        .bqn_call2
+        .call(&mut BQNFFI.get_store_unsafe(), f, w, x)?)
 }
 
 pub fn bqn_copy(v: BQNV) -> Result<BQNV> {
-    Ok(BQNFFI.bqn_copy.call(BQNFFI.get_store_unsafe(), v)?)
+    Ok(BQNFFI.bqn_copy.call(&mut BQNFFI.get_store_unsafe(), v)?)
 }
 
 pub fn bqn_directArrType(v: BQNV) -> Result<u32> {
     Ok(BQNFFI
         .bqn_directArrType
-        .call(BQNFFI.get_store_unsafe(), v)?)
+        .call(&mut BQNFFI.get_store_unsafe(), v)?)
 }
 
 pub fn bqn_eval(v: BQNV) -> Result<BQNV> {
-    Ok(BQNFFI.bqn_eval.call(BQNFFI.get_store_unsafe(), v)?)
+    Ok(BQNFFI.bqn_eval.call(&mut BQNFFI.get_store_unsafe(), v)?)
 }
 
 pub fn bqn_free(v: BQNV) -> Result<()> {
-    Ok(BQNFFI.bqn_free.call(BQNFFI.get_store_unsafe(), v)?)
+    Ok(BQNFFI.bqn_free.call(&mut BQNFFI.get_store_unsafe(), v)?)
 }
 
 pub fn bqn_getField(ns: BQNV, name: BQNV) -> Result<BQNV> {
     Ok(BQNFFI
         .bqn_getField
-        .call(BQNFFI.get_store_unsafe(), ns, name)?)
+        .call(&mut BQNFFI.get_store_unsafe(), ns, name)?)
 }
 
 pub fn bqn_hasField(ns: BQNV, name: BQNV) -> Result<bool> {
     Ok(BQNFFI
         .bqn_hasField
-        .call(BQNFFI.get_store_unsafe(), ns, name)?
+        .call(&mut BQNFFI.get_store_unsafe(), ns, name)?
         != 0)
 }
 
 pub fn bqn_init() -> Result<()> {
-    Ok(BQNFFI.bqn_init.call(BQNFFI.get_store_unsafe())?)
-}
-
-pub fn bqn_makeBoundFn1(_f: bindings::bqn_boundFn1, _obj: BQNV) -> Result<BQNV> {
-    Err(Error::NotSupported(
-        "BoundFns are not supported with WASI backend".into(),
-    ))
-}
-
-pub fn bqn_makeBoundFn2(_f: bindings::bqn_boundFn2, _obj: BQNV) -> Result<BQNV> {
-    Err(Error::NotSupported(
-        "BoundFns are not supported with WASI backend".into(),
-    ))
+    Ok(BQNFFI.bqn_init.call(&mut BQNFFI.get_store_unsafe())?)
+}
+
+pub fn bqn_makeBoundFn1(f: bindings::bqn_boundFn1, obj: BQNV) -> Result<BQNV> {
+    let f = f.ok_or_else(|| Error::NotSupported("BoundFn without a function".into()))?;
+    let idx = trampoline(f, f as usize, |store, env| {
+        Function::new_typed_with_env(
+            store,
+            env,
+            |mut env: FunctionEnvMut<unsafe extern "C" fn(BQNV, BQNV) -> BQNV>,
+             obj: BQNV,
+             x: BQNV|
+             -> BQNV {
+                let f = *env.data();
+                let mut store = env.as_store_mut();
+                let _active = ActiveStore::set(&mut store);
+                unsafe { f(obj, x) }
+            },
+        )
+    })?;
+    Ok(BQNFFI
+        .bqn_makeBoundFn1
+        .call(&mut BQNFFI.get_store_unsafe(), idx, obj)?)
+}
+
+pub fn bqn_makeBoundFn2(f: bindings::bqn_boundFn2, obj: BQNV) -> Result<BQNV> {
+    let f = f.ok_or_else(|| Error::NotSupported("BoundFn without a function".into()))?;
+    let idx = trampoline(f, f as usize, |store, env| {
+        Function::new_typed_with_env(
+            store,
+            env,
+            |mut env: FunctionEnvMut<unsafe extern "C" fn(BQNV, BQNV, BQNV) -> BQNV>,
+             obj: BQNV,
+             w: BQNV,
+             x: BQNV|
+             -> BQNV {
+                let f = *env.data();
+                let mut store = env.as_store_mut();
+                let _active = ActiveStore::set(&mut store);
+                unsafe { f(obj, w, x) }
+            },
+        )
+    })?;
+    Ok(BQNFFI
+        .bqn_makeBoundFn2
+        .call(&mut BQNFFI.get_store_unsafe(), idx, obj)?)
 }
 
 pub fn bqn_makeChar(c: u32) -> Result<BQNV> {
-    Ok(BQNFFI.bqn_makeChar.call(BQNFFI.get_store_unsafe(), c)?)
+    Ok(BQNFFI
+        .bqn_makeChar
+        .call(&mut BQNFFI.get_store_unsafe(), c)?)
 }
 
 pub fn bqn_makeF64(d: f64) -> Result<BQNV> {
-    Ok(BQNFFI.bqn_makeF64.call(BQNFFI.get_store_unsafe(), d)?)
+    Ok(BQNFFI.bqn_makeF64.call(&mut BQNFFI.get_store_unsafe(), d)?)
 }
 
 pub fn bqn_makeF64Vec(a: &[f64]) -> Result<BQNV> {
@@ -313,6 +475,58 @@ pub fn bqn_makeI8Vec(a: &[i8]) -> Result<BQNV> {
     })
 }
 
+// Copies `shape` and `a` into WASM memory and calls one of the `bqn_make*Arr` functions
+#[cfg(feature = "ndarray")]
+fn make_arr<T: ValueType>(
+    f: &TypedFunction<(u32, WasmPtr<u32>, WasmPtr<u32>), BQNV>,
+    shape: &[usize],
+    a: &[T],
+) -> Result<BQNV> {
+    // In 32-bit WASI, usize is u32 so we need to convert the shape
+    let shape = shape
+        .iter()
+        .map(|&s| s.try_into())
+        .collect::<std::result::Result<Vec<u32>, _>>()?;
+    with_buf(&shape, |shape, store, shape_ptr| {
+        let mem = BQNFFI.memory.view(store);
+        shape_ptr
+            .slice(&mem, shape.len().try_into()?)?
+            .write_slice(shape)?;
+        with_buf(a, |buf, store, ptr| {
+            let mem = BQNFFI.memory.view(store);
+            let tptr: WasmPtr<T> = ptr.cast();
+            tptr.slice(&mem, buf.len().try_into()?)?.write_slice(buf)?;
+
+            Ok(f.call(store, shape.len().try_into()?, shape_ptr, ptr)?)
+        })
+    })
+}
+
+#[cfg(feature = "ndarray")]
+pub fn bqn_makeF64Arr(shape: &[usize], a: &[f64]) -> Result<BQNV> {
+    make_arr(&BQNFFI.bqn_makeF64Arr, shape, a)
+}
+
+#[cfg(feature = "ndarray")]
+pub fn bqn_makeI32Arr(shape: &[usize], a: &[i32]) -> Result<BQNV> {
+    make_arr(&BQNFFI.bqn_makeI32Arr, shape, a)
+}
+
+#[cfg(feature = "ndarray")]
+pub fn bqn_makeI16Arr(shape: &[usize], a: &[i16]) -> Result<BQNV> {
+    make_arr(&BQNFFI.bqn_makeI16Arr, shape, a)
+}
+
+#[cfg(feature = "ndarray")]
+pub fn bqn_makeI8Arr(shape: &[usize], a: &[i8]) -> Result<BQNV> {
+    make_arr(&BQNFFI.bqn_makeI8Arr, shape, a)
+}
+
+#[cfg(feature = "ndarray")]
+pub fn bqn_makeC32Arr(shape: &[usize], a: &[u32]) -> Result<BQNV> {
+    make_arr(&BQNFFI.bqn_makeC32Arr, shape, a)
+}
+
 pub fn bqn_makeObjVec(a: &[BQNV]) -> Result<BQNV> {
     with_buf(a, |buf, store, ptr| {
         let mem = BQNFFI.memory.view(store);
@@ -337,7 +551,7 @@ pub fn bqn_makeUTF8Str(s: &str) -> Result<BQNV> {
 pub fn bqn_pick(v: BQNV, pos: usize) -> Result<BQNV> {
     Ok(BQNFFI
         .bqn_pick
-        .call(BQNFFI.get_store_unsafe(), v, pos as u32)?)
+        .call(&mut BQNFFI.get_store_unsafe(), v, pos as u32)?)
 }
 
 pub fn bqn_readC32Arr(v: BQNV, buf: &mut [u32]) -> Result<()> {
@@ -350,11 +564,13 @@ pub fn bqn_readC32Arr(v: BQNV, buf: &mut [u32]) -> Result<()> {
 }
 
 pub fn bqn_readChar(v: BQNV) -> Result<u32> {
-    Ok(BQNFFI.bqn_readChar.call(BQNFFI.get_store_unsafe(), v)?)
+    Ok(BQNFFI
+        .bqn_readChar
+        .call(&mut BQNFFI.get_store_unsafe(), v)?)
 }
 
 pub fn bqn_readF64(v: BQNV) -> Result<f64> {
-    Ok(BQNFFI.bqn_readF64.call(BQNFFI.get_store_unsafe(), v)?)
+    Ok(BQNFFI.bqn_readF64.call(&mut BQNFFI.get_store_unsafe(), v)?)
 }
 
 pub fn bqn_readF64Arr(v: BQNV, buf: &mut [f64]) -> Result<()> {
@@ -368,6 +584,39 @@ pub fn bqn_readF64Arr(v: BQNV, buf: &mut [f64]) -> Result<()> {
     Ok(())
 }
 
+pub fn bqn_readI32Arr(v: BQNV, buf: &mut [i32]) -> Result<()> {
+    with_buf_mut(buf, |buf, store, ptr| {
+        BQNFFI.bqn_readI32Arr.call(store, v, ptr)?;
+        let ptr: WasmPtr<i32> = ptr.cast();
+        let mem = BQNFFI.memory.view(store);
+        ptr.slice(&mem, buf.len().try_into()?)?.read_slice(buf)?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+pub fn bqn_readI16Arr(v: BQNV, buf: &mut [i16]) -> Result<()> {
+    with_buf_mut(buf, |buf, store, ptr| {
+        BQNFFI.bqn_readI16Arr.call(store, v, ptr)?;
+        let ptr: WasmPtr<i16> = ptr.cast();
+        let mem = BQNFFI.memory.view(store);
+        ptr.slice(&mem, buf.len().try_into()?)?.read_slice(buf)?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+pub fn bqn_readI8Arr(v: BQNV, buf: &mut [i8]) -> Result<()> {
+    with_buf_mut(buf, |buf, store, ptr| {
+        BQNFFI.bqn_readI8Arr.call(store, v, ptr)?;
+        let ptr: WasmPtr<i8> = ptr.cast();
+        let mem = BQNFFI.memory.view(store);
+        ptr.slice(&mem, buf.len().try_into()?)?.read_slice(buf)?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
 pub fn bqn_readObjArr(v: BQNV, buf: &mut [BQNV]) -> Result<()> {
     with_buf_mut(buf, |buf, store, ptr| {
         BQNFFI.bqn_readObjArr.call(store, v, ptr)?;
@@ -379,11 +628,11 @@ pub fn bqn_readObjArr(v: BQNV, buf: &mut [BQNV]) -> Result<()> {
 }
 
 pub fn bqn_type(v: BQNV) -> Result<i32> {
-    Ok(BQNFFI.bqn_type.call(BQNFFI.get_store_unsafe(), v)?)
+    Ok(BQNFFI.bqn_type.call(&mut BQNFFI.get_store_unsafe(), v)?)
 }
 
 pub fn bqn_rank(v: BQNV) -> Result<usize> {
-    Ok(BQNFFI.bqn_rank.call(BQNFFI.get_store_unsafe(), v)? as usize)
+    Ok(BQNFFI.bqn_rank.call(&mut BQNFFI.get_store_unsafe(), v)? as usize)
 }
 
 pub fn bqn_shape(v: BQNV, buf: &mut [usize]) -> Result<()> {
@@ -0,0 +1,120 @@
+use crate::{eval, BQNValue, Result, LOCK};
+use parking_lot::Mutex;
+use std::{collections::HashMap, sync::OnceLock};
+
+// Evaluated values keyed by their source code. A cache is cleared when it's full, so that code
+// generated at runtime can't grow it without bounds.
+pub(crate) type Cache = OnceLock<Mutex<HashMap<String, BQNValue>>>;
+const CACHE_SIZE: usize = 1024;
+
+// Values evaluated by `eval_cached`
+static CACHE: Cache = OnceLock::new();
+
+// Primitives and system functions used by the crate
+pub(crate) static FMT: Compiled = Compiled::new("•Fmt");
+pub(crate) static REPR: Compiled = Compiled::new("•Repr");
+pub(crate) static MATCH: Compiled = Compiled::new("≡");
+pub(crate) static HASH: Compiled = Compiled::new("•Hash");
+
+/// A BQN value that is evaluated from source code on first use
+///
+/// `Compiled` can be stored in a `static`, so that a function used in a hot loop is compiled only
+/// once. Unlike [`eval_cached`], there's no lookup by the source code on every use.
+///
+/// # Examples
+/// ```
+/// # use cbqn::{BQNValue, Compiled, Error};
+/// static SUM: Compiled = Compiled::new("+´");
+///
+/// for n in 1..=3 {
+///     let sum = SUM.call1(&BQNValue::from(vec![n; 4]))?;
+///     assert_eq!(sum.to_f64()?, 4.0 * n as f64);
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub struct Compiled {
+    bqn: &'static str,
+    eval: fn(&str) -> Result<BQNValue>,
+    value: OnceLock<BQNValue>,
+}
+
+impl Compiled {
+    /// Creates a handle that evaluates `bqn` when it's first used
+    pub const fn new(bqn: &'static str) -> Compiled {
+        Compiled::with_eval(bqn, eval)
+    }
+
+    // Evaluates the code with `eval`, for the values used by the backend to implement `eval`
+    pub(crate) const fn with_eval(
+        bqn: &'static str,
+        eval: fn(&str) -> Result<BQNValue>,
+    ) -> Compiled {
+        Compiled {
+            bqn,
+            eval,
+            value: OnceLock::new(),
+        }
+    }
+
+    /// Returns the value of the code, evaluating it if this is the first use
+    ///
+    /// An error from the evaluation is returned and the code is evaluated again on the next use.
+    pub fn get(&self) -> Result<&BQNValue> {
+        if let Some(v) = self.value.get() {
+            return Ok(v);
+        }
+        let v = (self.eval)(self.bqn)?;
+        Ok(self.value.get_or_init(|| v))
+    }
+
+    /// Calls the value as a function with one argument
+    pub fn call1(&self, x: &BQNValue) -> Result<BQNValue> {
+        self.get()?.call1(x)
+    }
+
+    /// Calls the value as a function with two arguments
+    pub fn call2(&self, w: &BQNValue, x: &BQNValue) -> Result<BQNValue> {
+        self.get()?.call2(w, x)
+    }
+}
+
+/// Evaluates BQN code once and returns the cached value on later calls with the same code
+///
+/// Meant for code that evaluates to a function or a constant that is used repeatedly, unlike the
+/// [`BQN!`](crate::BQN) macro, which evaluates its code on every use. Errors aren't cached.
+///
+/// The cache holds up to 1024 entries and is cleared when it's full, after which the code is
+/// evaluated again. Side effects of the code usually happen only once and the calls usually return
+/// the same value, but code that relies on either, such as a function with state, should be stored
+/// in a [`Compiled`] or a variable instead.
+///
+/// # Examples
+/// ```
+/// # use cbqn::{BQNValue, Error, eval_cached};
+/// for n in 1..=3 {
+///     let square = eval_cached("×˜")?;
+///     assert_eq!(square.call1(&n.into())?.to_f64()?, (n * n) as f64);
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub fn eval_cached(bqn: &str) -> Result<BQNValue> {
+    eval_with_cache(&CACHE, bqn)
+}
+
+// Evaluates `bqn` or returns its value from `cache`
+pub(crate) fn eval_with_cache(cache: &Cache, bqn: &str) -> Result<BQNValue> {
+    // CBQN is locked first, as the cache is also used from bound functions that are run while
+    // CBQN is locked
+    let _l = LOCK.lock();
+    let cache = cache.get_or_init(Default::default);
+    if let Some(v) = cache.lock().get(bqn) {
+        return v.try_clone();
+    }
+    let v = eval(bqn)?;
+    let mut cache = cache.lock();
+    if cache.len() >= CACHE_SIZE {
+        cache.clear();
+    }
+    cache.insert(bqn.to_owned(), v.try_clone()?);
+    Ok(v)
+}
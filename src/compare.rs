@@ -0,0 +1,89 @@
+use crate::{
+    cache::{HASH, MATCH},
+    BQNValue, Compiled,
+};
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+// Grading values that contain operations is an error, which can't be caught in WASI builds, so
+// they are detected first. Values that don't match can still tie in grade, such as `1` and `<1`,
+// so grading both orders detects ties, which are broken by the next key. Returns 2 if no key
+// tells the values apart.
+static COMPARE: Compiled = Compiled::new(
+    "D←{0=•Type 𝕩 ? ∧´𝕊¨⥊𝕩 ; 3>•Type 𝕩}
+    C←{(⊑⍋𝕨‿𝕩)-⊑⍋𝕩‿𝕨}
+    O←{r←C´¨𝕩 ⋄ ⊑(0≠r)/r∾2}
+    {𝕨≡𝕩 ? 0 ; (2⊥D¨𝕨‿𝕩)◶⟨O∘{⟨•Hash¨𝕩, •Type¨𝕩⟩}, 1˙, ¯1˙, O∘{⟨𝕩, •Hash¨𝕩⟩}⟩ 𝕨‿𝕩}",
+);
+
+/// Values are equal if they match, `𝕨≡𝕩`
+///
+/// # Examples
+/// ```
+/// # use cbqn::{BQN, BQNValue, eval, Error};
+/// assert_eq!(BQN!("⟨1, \"ab\"⟩")?, BQN!("1⊸⋈\"ab\"")?);
+/// assert_ne!(BQN!("1‿2")?, BQN!("1‿2‿3")?);
+/// # Ok::<(), Error>(())
+/// ```
+impl PartialEq for BQNValue {
+    fn eq(&self, other: &BQNValue) -> bool {
+        MATCH
+            .call2(self, other)
+            .and_then(|r| r.to_f64())
+            .is_ok_and(|r| r == 1.0)
+    }
+}
+
+impl Eq for BQNValue {}
+
+/// Values are ordered like BQN orders them when sorting with `∧`
+///
+/// Numbers come before characters, and arrays are compared element by element. See
+/// [array ordering](https://mlochbaum.github.io/BQN/doc/order.html#array-ordering) in the BQN
+/// documentation. BQN can't order functions, modifiers and namespaces, so values that contain
+/// them are ordered after other values, and among themselves by their hash and type. Values that
+/// don't match but tie in BQN's ordering, like `1` and `<1`, are ordered by their hash, so only
+/// matching values compare as equal.
+///
+/// Values that can't be told apart this way, and values that the backend fails to compare, are
+/// ordered by their hash and then by the identity of the CBQN objects, which is stable while the
+/// values are alive.
+///
+/// # Examples
+/// ```
+/// # use cbqn::{BQN, BQNValue, eval, Error};
+/// let mut values = vec![BQN!("\"b\"")?, BQN!("'a'")?, BQN!("2")?, BQN!("\"ab\"")?];
+/// values.sort();
+/// assert_eq!(values, [BQN!("2")?, BQN!("'a'")?, BQN!("\"ab\"")?, BQN!("\"b\"")?]);
+/// # Ok::<(), Error>(())
+/// ```
+impl Ord for BQNValue {
+    fn cmp(&self, other: &BQNValue) -> Ordering {
+        match COMPARE.call2(self, other).and_then(|r| r.to_f64()) {
+            // ¯1, 0 or 1 when BQN tells the order
+            Ok(r) if r.abs() <= 1.0 => r.total_cmp(&0.0),
+            _ => hash(self)
+                .total_cmp(&hash(other))
+                .then_with(|| self.value.cmp(&other.value)),
+        }
+    }
+}
+
+impl PartialOrd for BQNValue {
+    fn partial_cmp(&self, other: &BQNValue) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Hashes the value with `•Hash`, so that values that match have the same hash
+impl Hash for BQNValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(hash(self).to_bits());
+    }
+}
+
+fn hash(value: &BQNValue) -> f64 {
+    HASH.call1(value).and_then(|h| h.to_f64()).unwrap_or(0.0)
+}
@@ -1,23 +1,103 @@
-use crate::{backend::*, macros::*, BQNValue, LOCK};
+use crate::{backend::*, macros::*, try_init, BQNValue, LOCK};
+
+// Passes the ownership of the values to CBQN
+fn into_raw(values: Vec<BQNValue>) -> Vec<BQNV> {
+    values
+        .into_iter()
+        .map(|v| {
+            let val = v.value;
+            std::mem::forget(v);
+            val
+        })
+        .collect()
+}
+
+/// Fallible constructors
+///
+/// The `From` implementations of `BQNValue` panic if CBQN fails to allocate the value, e.g. when
+/// the WASI backend runs out of memory. These functions return the failure as an error instead.
+impl BQNValue {
+    /// Creates a BQN number
+    pub fn try_from_f64(v: f64) -> Result<BQNValue> {
+        try_init()?;
+        let _l = LOCK.lock();
+        Ok(BQNValue::new(bqn_makeF64(v)?))
+    }
+
+    /// Creates a BQN character
+    pub fn try_from_char(v: char) -> Result<BQNValue> {
+        try_init()?;
+        let _l = LOCK.lock();
+        Ok(BQNValue::new(bqn_makeChar(v as u32)?))
+    }
+
+    /// Creates a BQN string
+    pub fn try_from_str(v: &str) -> Result<BQNValue> {
+        try_init()?;
+        let _l = LOCK.lock();
+        Ok(BQNValue::new(bqn_makeUTF8Str(v)?))
+    }
+
+    /// Creates a list of BQN strings
+    pub fn try_from_strs<S: AsRef<str>>(v: &[S]) -> Result<BQNValue> {
+        let strs = v
+            .iter()
+            .map(|s| BQNValue::try_from_str(s.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        BQNValue::try_from_values(strs)
+    }
+
+    /// Creates a list of BQN numbers
+    pub fn try_from_f64_slice(v: &[f64]) -> Result<BQNValue> {
+        try_init()?;
+        let _l = LOCK.lock();
+        Ok(BQNValue::new(bqn_makeF64Vec(v)?))
+    }
+
+    /// Creates a list of BQN numbers
+    pub fn try_from_i32_slice(v: &[i32]) -> Result<BQNValue> {
+        try_init()?;
+        let _l = LOCK.lock();
+        Ok(BQNValue::new(bqn_makeI32Vec(v)?))
+    }
+
+    /// Creates a list of BQN numbers
+    pub fn try_from_i16_slice(v: &[i16]) -> Result<BQNValue> {
+        try_init()?;
+        let _l = LOCK.lock();
+        Ok(BQNValue::new(bqn_makeI16Vec(v)?))
+    }
+
+    /// Creates a list of BQN numbers
+    pub fn try_from_i8_slice(v: &[i8]) -> Result<BQNValue> {
+        try_init()?;
+        let _l = LOCK.lock();
+        Ok(BQNValue::new(bqn_makeI8Vec(v)?))
+    }
+
+    /// Creates a BQN list of `v`
+    pub fn try_from_values(v: Vec<BQNValue>) -> Result<BQNValue> {
+        try_init()?;
+        let _l = LOCK.lock();
+        Ok(BQNValue::new(bqn_makeObjVec(&into_raw(v))?))
+    }
+}
 
 impl From<f64> for BQNValue {
     fn from(v: f64) -> BQNValue {
-        let _l = LOCK.lock();
-        BQNValue::new(bqn_makeF64(v).unwrap())
+        BQNValue::try_from_f64(v).unwrap()
     }
 }
 
 impl From<i32> for BQNValue {
     fn from(v: i32) -> BQNValue {
-        let _l = LOCK.lock();
-        BQNValue::new(bqn_makeF64(v as f64).unwrap())
+        BQNValue::try_from_f64(v as f64).unwrap()
     }
 }
 
 impl From<char> for BQNValue {
     fn from(v: char) -> BQNValue {
-        let _l = LOCK.lock();
-        BQNValue::new(bqn_makeChar(v as u32).unwrap())
+        BQNValue::try_from_char(v).unwrap()
     }
 }
 
@@ -30,124 +110,59 @@ impl_from_string_like_vec!(String);
 
 impl<const N: usize> From<[&str; N]> for BQNValue {
     fn from(arr: [&str; N]) -> BQNValue {
-        crate::INIT.call_once(|| {
-            let _l = LOCK.lock();
-            bqn_init().unwrap()
-        });
-        let mut strs = Vec::with_capacity(N);
-        let _l = LOCK.lock();
-        for s in arr {
-            strs.push(bqn_makeUTF8Str(s).unwrap());
-        }
-        BQNValue::new(bqn_makeObjVec(&strs).unwrap())
+        BQNValue::try_from_strs(&arr).unwrap()
     }
 }
 
 impl<const N: usize> From<[String; N]> for BQNValue {
     fn from(arr: [String; N]) -> BQNValue {
-        crate::INIT.call_once(|| {
-            let _l = LOCK.lock();
-            bqn_init().unwrap();
-        });
-        let mut strs = Vec::with_capacity(N);
-        let _l = LOCK.lock();
-        for s in arr {
-            strs.push(bqn_makeUTF8Str(&s).unwrap());
-        }
-        BQNValue::new(bqn_makeObjVec(&strs).unwrap())
+        BQNValue::try_from_strs(&arr).unwrap()
     }
 }
 
-impl_from_array!(f64, bqn_makeF64Vec);
-impl_from_array!(i32, bqn_makeI32Vec);
-impl_from_array!(i16, bqn_makeI16Vec);
-impl_from_array!(i8, bqn_makeI8Vec);
+impl_from_array!(f64, try_from_f64_slice);
+impl_from_array!(i32, try_from_i32_slice);
+impl_from_array!(i16, try_from_i16_slice);
+impl_from_array!(i8, try_from_i8_slice);
 impl<const N: usize> From<[BQNValue; N]> for BQNValue {
     fn from(arr: [BQNValue; N]) -> BQNValue {
-        crate::INIT.call_once(|| {
-            let _l = LOCK.lock();
-            bqn_init().unwrap()
-        });
-        let elems = arr
-            .into_iter()
-            .map(|v| {
-                let val = v.value;
-                std::mem::forget(v);
-                val
-            })
-            .collect::<Vec<_>>();
-        let _l = LOCK.lock();
-        BQNValue::new(bqn_makeObjVec(&elems).unwrap())
+        BQNValue::try_from_values(arr.into()).unwrap()
     }
 }
 
-impl_from_slice!(&[f64], bqn_makeF64Vec);
-impl_from_slice!(&[i32], bqn_makeI32Vec);
-impl_from_slice!(&[i16], bqn_makeI16Vec);
-impl_from_slice!(&[i8], bqn_makeI8Vec);
+impl_from_slice!(&[f64], try_from_f64_slice);
+impl_from_slice!(&[i32], try_from_i32_slice);
+impl_from_slice!(&[i16], try_from_i16_slice);
+impl_from_slice!(&[i8], try_from_i8_slice);
 impl From<&[BQNValue]> for BQNValue {
     fn from(arr: &[BQNValue]) -> BQNValue {
-        crate::INIT.call_once(|| {
-            let _l = LOCK.lock();
-            bqn_init().unwrap()
-        });
-
-        let _l = LOCK.lock();
-        let elems = arr
-            .iter()
-            .map(|v| bqn_copy(v.value).expect("bqn_copy"))
-            .collect::<Vec<_>>();
-        BQNValue::new(bqn_makeObjVec(&elems).unwrap())
+        arr.iter()
+            .map(BQNValue::try_clone)
+            .collect::<Result<Vec<_>>>()
+            .and_then(BQNValue::try_from_values)
+            .unwrap()
     }
 }
 
-impl_from_vec!(f64, bqn_makeF64Vec);
-impl_from_vec!(i32, bqn_makeI32Vec);
-impl_from_vec!(i16, bqn_makeI16Vec);
-impl_from_vec!(i8, bqn_makeI8Vec);
+impl_from_vec!(f64, try_from_f64_slice);
+impl_from_vec!(i32, try_from_i32_slice);
+impl_from_vec!(i16, try_from_i16_slice);
+impl_from_vec!(i8, try_from_i8_slice);
 impl From<Vec<BQNValue>> for BQNValue {
     fn from(arr: Vec<BQNValue>) -> BQNValue {
-        crate::INIT.call_once(|| {
-            let _l = LOCK.lock();
-            bqn_init().unwrap();
-        });
-
-        let elems = arr
-            .into_iter()
-            .map(|v| {
-                let val = v.value;
-                std::mem::forget(v);
-                val
-            })
-            .collect::<Vec<_>>();
-        let _l = LOCK.lock();
-        BQNValue::new(bqn_makeObjVec(&elems).unwrap())
+        BQNValue::try_from_values(arr).unwrap()
     }
 }
 
-impl_from_iterator!(f64, bqn_makeF64Vec);
-impl_from_iterator!(i32, bqn_makeI32Vec);
-impl_from_iterator!(i16, bqn_makeI16Vec);
-impl_from_iterator!(i8, bqn_makeI8Vec);
+impl_from_iterator!(f64, try_from_f64_slice);
+impl_from_iterator!(i32, try_from_i32_slice);
+impl_from_iterator!(i16, try_from_i16_slice);
+impl_from_iterator!(i8, try_from_i8_slice);
 impl FromIterator<BQNValue> for BQNValue {
     fn from_iter<T>(iter: T) -> BQNValue
     where
         T: IntoIterator<Item = BQNValue>,
     {
-        crate::INIT.call_once(|| {
-            let _l = LOCK.lock();
-            bqn_init().unwrap();
-        });
-
-        let elems = iter
-            .into_iter()
-            .map(|v| {
-                let val = v.value;
-                std::mem::forget(v);
-                val
-            })
-            .collect::<Vec<_>>();
-        let _l = LOCK.lock();
-        BQNValue::new(bqn_makeObjVec(&elems).unwrap())
+        BQNValue::try_from_values(iter.into_iter().collect()).unwrap()
     }
 }
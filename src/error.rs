@@ -0,0 +1,193 @@
+use crate::BQNValue;
+use std::fmt;
+
+/// An error thrown by BQN code
+///
+/// Keeps the value that was thrown, so that payloads thrown with `𝕨!𝕩`, such as `⟨code, data⟩`,
+/// can be inspected from Rust. Errors from the compiler also carry the location of the error in
+/// the evaluated source.
+///
+/// # Examples
+/// ```
+/// # use cbqn::{eval, Error};
+/// match eval("a←1\n2+b") {
+///     Err(Error::BQN(e)) => {
+///         assert_eq!(e.message(), "Undefined identifier");
+///         let span = e.span().unwrap();
+///         assert_eq!((span.line, span.start, span.end), (2, 2, 3));
+///     }
+///     r => panic!("expected a BQN error, got {r:?}"),
+/// }
+/// ```
+///
+/// # Backend support
+///
+/// In WASI backend, the thrown value isn't available as CBQN only reports errors by printing them.
+/// [`value`](BQNError::value) returns the message as a BQN string instead.
+#[derive(Debug)]
+pub struct BQNError {
+    value: BQNValue,
+    message: String,
+    location: Option<Location>,
+}
+
+/// The location of an error in the source code
+///
+/// Columns count characters, not bytes, and start from 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    /// Line of the error, starting from 1
+    pub line: usize,
+    /// Column of the first character of the error
+    pub start: usize,
+    /// Column after the last character of the error
+    pub end: usize,
+}
+
+// The location as CBQN reports it. When the source wasn't given a name, CBQN only includes the
+// line itself and not its number, which is then looked up from the source.
+#[derive(Debug)]
+struct Location {
+    line: Option<usize>,
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+impl BQNError {
+    /// The value that was thrown
+    pub fn value(&self) -> &BQNValue {
+        &self.value
+    }
+
+    /// The error message
+    ///
+    /// If the thrown value isn't a string, this is the value formatted with `•Fmt`.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Location of the error in the evaluated source
+    ///
+    /// Only errors from compiling the source have a location.
+    pub fn span(&self) -> Option<Span> {
+        self.location.as_ref().and_then(|l| {
+            Some(Span {
+                line: l.line?,
+                start: l.start,
+                end: l.end,
+            })
+        })
+    }
+
+    #[cfg(feature = "native-backend")]
+    pub(crate) fn from_value(value: BQNValue) -> BQNError {
+        let (message, location) = match value.to_string() {
+            Ok(msg) => parse_message(&msg),
+            Err(_) => (format_value(&value), None),
+        };
+        BQNError {
+            value,
+            message,
+            location,
+        }
+    }
+
+    // CBQN prints errors as `Error: ` followed by the message, possibly followed by the location
+    // and a stack trace
+    #[cfg(feature = "wasi-backend")]
+    pub(crate) fn from_stderr(stderr: &str) -> BQNError {
+        let stderr = stderr.trim_end();
+        let stderr = stderr.strip_prefix("Error: ").unwrap_or(stderr);
+        let (message, location) = match parse_message(stderr) {
+            (message, Some(location)) => (message, Some(location)),
+            _ => (stderr.lines().next().unwrap_or("").to_owned(), None),
+        };
+        BQNError {
+            value: BQNValue::from(message.as_str()),
+            message,
+            location,
+        }
+    }
+
+    // Looks up the line number of the error from the source that was evaluated
+    pub(crate) fn with_source(mut self, source: &str) -> BQNError {
+        if let Some(l) = self.location.as_mut().filter(|l| l.line.is_none()) {
+            l.line = source
+                .split('\n')
+                .position(|line| line == l.text)
+                .map(|i| i + 1);
+        }
+        self
+    }
+}
+
+impl fmt::Display for BQNError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(span) = self.span() {
+            write!(f, " (line {}, column {})", span.line, span.start + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for BQNError {}
+
+#[cfg(feature = "native-backend")]
+fn format_value(value: &BQNValue) -> String {
+    crate::cache::FMT
+        .call1(value)
+        .and_then(|s| s.to_string())
+        .unwrap_or_else(|_| "Error".to_owned())
+}
+
+// Compiler errors end with the location of the error in one of these forms:
+//
+// ```text
+// at 2+b                   path:2:
+//      ^                     2+b
+//                              ^
+// ```
+fn parse_message(msg: &str) -> (String, Option<Location>) {
+    let lines = msg.split('\n').collect::<Vec<_>>();
+    let n = lines.len();
+    if n < 3 {
+        return (msg.to_owned(), None);
+    }
+    let caret = lines[n - 1];
+    let padding = caret
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .count();
+    let width = caret.chars().skip(padding).count();
+    if width == 0 || caret.chars().skip(padding).any(|c| c != '^') {
+        return (msg.to_owned(), None);
+    }
+    let (text, pad, line, message_lines) = if let Some(text) = lines[n - 2].strip_prefix("at ") {
+        (text, 3, None, n - 2)
+    } else if let (Some(text), Some(line)) =
+        (lines[n - 2].strip_prefix("  "), line_number(lines[n - 3]))
+    {
+        (text, 2, Some(line), n - 3)
+    } else {
+        return (msg.to_owned(), None);
+    };
+    let Some(start) = padding.checked_sub(pad) else {
+        return (msg.to_owned(), None);
+    };
+    let location = Location {
+        line,
+        text: text.to_owned(),
+        start,
+        end: start + width,
+    };
+    (lines[..message_lines].join("\n"), Some(location))
+}
+
+// Parses the line number from `path:line:`
+fn line_number(s: &str) -> Option<usize> {
+    let s = s.strip_suffix(':')?;
+    let (_, line) = s.rsplit_once(':')?;
+    line.parse().ok()
+}
@@ -0,0 +1,372 @@
+use crate::namespace::{make_namespace, normalize_name};
+use crate::session::ReplMode;
+use crate::{BQNValue, Compiled, Error, Result};
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+// The primitive and system value lists given to •ReBQN replace the defaults, so the defaults that
+// aren't overridden are added to the lists
+static MAKE_EVALUATOR: Compiled = Compiled::new(
+    r#"{𝕊 ip‿prims‿is‿sys‿mode:
+        ps←•primitives ⋄ ns←•listsys
+        p←prims∾˜(ip∧¬(⊑¨ps)∊⊑¨prims)/ps
+        s←sys∾˜(is∧¬ns∊⊑¨sys)/ns
+        •ReBQN{primitives⇐p ⋄ system⇐s ⋄ repl⇐mode}
+    }"#,
+);
+static EVAL_WITH_ARGS: Compiled = Compiled::new(r#"{r‿a 𝕊 src: ⟨•wdpath, "(eval)", a⟩ R src}"#);
+static MAKE_OUTPUT: Compiled = Compiled::new(
+    r#"{𝕊 ws‿wb‿fl:
+        out←{"•Out: Argument must be a string"!1==𝕩 ⋄ Ws 𝕩∾@+10 ⋄ 𝕩}
+        show←{Ws (•Fmt 𝕩)∾@+10 ⋄ 𝕩}
+        term←{
+            flush⇐{Fl 𝕩}
+            outRaw⇐{"•term.OutRaw: 𝕩 must be a list"!1==𝕩 ⋄ Wb -⟜@⍟(2=•Type)¨𝕩 ⋄ 𝕩}
+            errRaw⇐•term.ErrRaw ⋄ rawMode⇐•term.RawMode
+            charB⇐•term.CharB ⋄ charN⇐•term.CharN
+        }
+        ⟨out, show, term⟩
+    }"#,
+);
+
+/// Builder for an [`Evaluator`] with a custom set of primitives and system values
+///
+/// By default, the evaluator has the same primitives and system values as [`eval`](crate::eval).
+/// Primitives and system values added with [`primitive`](EvaluatorBuilder::primitive) and
+/// [`system_value`](EvaluatorBuilder::system_value) are added to those, replacing the existing
+/// ones with the same glyph or name.
+///
+/// # Examples
+/// ```
+/// # use cbqn::{BQNValue, Error, EvaluatorBuilder};
+/// let evaluator = EvaluatorBuilder::new()
+///     .primitive('⍳', BQNValue::fn2(|w, x| {
+///         BQNValue::from(w.to_f64().unwrap() * 10.0 + x.to_f64().unwrap())
+///     }))
+///     .system_value("answer", BQNValue::from(42.0))
+///     .build()?;
+/// assert_eq!(evaluator.eval("1⍳•answer")?.to_f64()?, 52.0);
+/// # Ok::<(), Error>(())
+/// ```
+///
+/// System values implemented in Rust:
+///
+/// ```
+/// # use cbqn::{BQNValue, Error, EvaluatorBuilder};
+/// let evaluator = EvaluatorBuilder::new()
+///     .system_fn1("log", |x| {
+///         println!("{}", x.to_string()?);
+///         Ok(x.clone())
+///     })
+///     .system_namespace(
+///         "db",
+///         [("query", BQNValue::closure1(|x| BQNValue::from(x.to_string().unwrap().len() as f64)))],
+///     )
+///     .build()?;
+/// assert_eq!(evaluator.eval(r#"•db.Query •Log "select""#)?.to_f64()?, 6.0);
+/// # Ok::<(), Error>(())
+/// ```
+pub struct EvaluatorBuilder {
+    primitives: Vec<(char, BQNValue)>,
+    system: Vec<(String, SystemValue)>,
+    inherit_primitives: bool,
+    inherit_system: bool,
+    repl: Option<ReplMode>,
+    output: Option<Box<dyn Write>>,
+}
+
+impl Default for EvaluatorBuilder {
+    /// Same as [`EvaluatorBuilder::new`]
+    fn default() -> EvaluatorBuilder {
+        EvaluatorBuilder::new()
+    }
+}
+
+impl EvaluatorBuilder {
+    /// Creates a builder for an evaluator that has the default primitives and system values
+    pub fn new() -> EvaluatorBuilder {
+        EvaluatorBuilder {
+            primitives: Vec::new(),
+            system: Vec::new(),
+            inherit_primitives: true,
+            inherit_system: true,
+            repl: None,
+            output: None,
+        }
+    }
+
+    /// Adds a primitive with the glyph `glyph`
+    ///
+    /// The role of the primitive is determined by the type of `value`: functions become
+    /// functions, and 1- and 2-modifiers become modifiers.
+    pub fn primitive(mut self, glyph: char, value: BQNValue) -> EvaluatorBuilder {
+        self.primitives.retain(|(g, _)| *g != glyph);
+        self.primitives.push((glyph, value));
+        self
+    }
+
+    /// Sets whether the evaluator has the default primitives in addition to the ones added with
+    /// [`primitive`](EvaluatorBuilder::primitive)
+    pub fn inherit_primitives(mut self, inherit: bool) -> EvaluatorBuilder {
+        self.inherit_primitives = inherit;
+        self
+    }
+
+    /// Adds a system value that is available as `•name` in the evaluated code
+    ///
+    /// The name is normalized like BQN names are: `my_value` is available as `•MyValue`,
+    /// `•myvalue` and `•my_value`.
+    pub fn system_value(mut self, name: &str, value: BQNValue) -> EvaluatorBuilder {
+        self.system
+            .push((name.to_owned(), SystemValue::Value(value)));
+        self
+    }
+
+    /// Adds a system function that is implemented by a Rust closure called with one argument
+    ///
+    /// An error returned from the closure is thrown as a BQN error. See [`BQNValue::try_fn1`]. The
+    /// function can only be called on the thread that built the evaluator.
+    ///
+    /// # Backend support
+    ///
+    /// In WASI backend, `BQN.wasm` must export a growable function table. See the crate README.
+    pub fn system_fn1<F>(mut self, name: &str, func: F) -> EvaluatorBuilder
+    where
+        F: Fn(&BQNValue) -> Result<BQNValue> + 'static,
+    {
+        self.system
+            .push((name.to_owned(), SystemValue::Fn1(Box::new(func))));
+        self
+    }
+
+    /// Adds a system function that is implemented by a Rust closure called with two arguments
+    ///
+    /// An error returned from the closure is thrown as a BQN error. See [`BQNValue::try_fn2`]. The
+    /// function can only be called on the thread that built the evaluator.
+    ///
+    /// # Backend support
+    ///
+    /// In WASI backend, `BQN.wasm` must export a growable function table. See the crate README.
+    pub fn system_fn2<F>(mut self, name: &str, func: F) -> EvaluatorBuilder
+    where
+        F: Fn(&BQNValue, &BQNValue) -> Result<BQNValue> + 'static,
+    {
+        self.system
+            .push((name.to_owned(), SystemValue::Fn2(Box::new(func))));
+        self
+    }
+
+    /// Adds a system value that is a namespace with the given fields
+    ///
+    /// The field names are normalized like BQN names are, so the fields of a system namespace
+    /// `db` with a field `query` can be accessed as `•db.Query`.
+    pub fn system_namespace<I, S>(mut self, name: &str, fields: I) -> EvaluatorBuilder
+    where
+        I: IntoIterator<Item = (S, BQNValue)>,
+        S: Into<String>,
+    {
+        let fields = fields.into_iter().map(|(n, v)| (n.into(), v)).collect();
+        self.system
+            .push((name.to_owned(), SystemValue::Namespace(fields)));
+        self
+    }
+
+    /// Sets whether the evaluator has the default system values in addition to the ones added
+    /// with [`system_value`](EvaluatorBuilder::system_value)
+    pub fn inherit_system(mut self, inherit: bool) -> EvaluatorBuilder {
+        self.inherit_system = inherit;
+        self
+    }
+
+    /// Makes the evaluator keep its scope between evaluations like a [`Session`](crate::Session)
+    pub fn repl(mut self, mode: ReplMode) -> EvaluatorBuilder {
+        self.repl = Some(mode);
+        self
+    }
+
+    /// Writes the output of `•Out`, `•Show` and `•term.OutRaw` to `sink` instead of stdout
+    ///
+    /// `•term.Flush` flushes `sink`. Output written to stderr with `•term.ErrRaw` isn't
+    /// redirected. Use an [`OutputBuffer`] to collect the output into a string.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{Error, EvaluatorBuilder, OutputBuffer};
+    /// let output = OutputBuffer::new();
+    /// let evaluator = EvaluatorBuilder::new().output(output.clone()).build()?;
+    /// evaluator.eval(r#"•Out "hello" ⋄ •Show 1‿2"#)?;
+    /// assert_eq!(output.take(), "hello\n⟨ 1 2 ⟩\n");
+    /// # Ok::<(), Error>(())
+    /// ```
+    ///
+    /// # Backend support
+    ///
+    /// In WASI backend, `BQN.wasm` must export a growable function table. See the crate README.
+    pub fn output<W: Write + 'static>(mut self, sink: W) -> EvaluatorBuilder {
+        self.output = Some(Box::new(sink));
+        self
+    }
+
+    /// Creates the evaluator
+    ///
+    /// Fails if the name of a system value or a field of a system namespace isn't a valid BQN
+    /// name.
+    pub fn build(self) -> Result<Evaluator> {
+        let primitives = self
+            .primitives
+            .into_iter()
+            .map(|(glyph, value)| BQNValue::from([BQNValue::from(glyph), value]))
+            .collect::<Vec<_>>();
+        let mut system: Vec<(String, BQNValue)> = Vec::with_capacity(self.system.len() + 3);
+        if let Some(sink) = self.output {
+            system.extend(output_system_values(sink)?);
+        }
+        for (name, value) in self.system {
+            let name = normalize_name(&name)?;
+            let value = match value {
+                SystemValue::Value(value) => value,
+                SystemValue::Namespace(fields) => make_namespace(fields)?,
+                SystemValue::Fn1(func) => BQNValue::try_fn1(func)?,
+                SystemValue::Fn2(func) => BQNValue::try_fn2(func)?,
+            };
+            system.retain(|(n, _)| *n != name);
+            system.push((name, value));
+        }
+        let system = system
+            .into_iter()
+            .map(|(name, value)| BQNValue::from([BQNValue::from(&name[..]), value]))
+            .collect::<Vec<_>>();
+        let repl = self.repl.map_or("none", ReplMode::as_str);
+
+        let rebqn = MAKE_EVALUATOR.call1(&BQNValue::from([
+            BQNValue::from(i32::from(self.inherit_primitives)),
+            BQNValue::from(primitives),
+            BQNValue::from(i32::from(self.inherit_system)),
+            BQNValue::from(system),
+            BQNValue::from(repl),
+        ]))?;
+        Ok(Evaluator { rebqn })
+    }
+}
+
+// Creates the system values `out`, `show` and `term` that write to `sink`
+fn output_system_values(sink: Box<dyn Write>) -> Result<Vec<(String, BQNValue)>> {
+    let sink = Rc::new(RefCell::new(sink));
+    let io_error = |e: io::Error| Error::CBQN(e.to_string());
+    let write_str = BQNValue::try_fn1({
+        let sink = sink.clone();
+        move |x| {
+            let s = x.to_string()?;
+            sink.borrow_mut()
+                .write_all(s.as_bytes())
+                .map_err(io_error)?;
+            Ok(x.clone())
+        }
+    })?;
+    let write_bytes = BQNValue::try_fn1({
+        let sink = sink.clone();
+        move |x| {
+            let bytes = x.to_u8_vec()?;
+            sink.borrow_mut().write_all(&bytes).map_err(io_error)?;
+            Ok(x.clone())
+        }
+    })?;
+    let flush = BQNValue::try_fn1(move |x| {
+        sink.borrow_mut().flush().map_err(io_error)?;
+        Ok(x.clone())
+    })?;
+    let values = MAKE_OUTPUT
+        .call1(&BQNValue::from([write_str, write_bytes, flush]))?
+        .to_bqnvalue_vec()?;
+    Ok(["out", "show", "term"]
+        .into_iter()
+        .map(String::from)
+        .zip(values)
+        .collect())
+}
+
+/// An `io::Write` sink that collects the output of an [`Evaluator`] into a buffer
+///
+/// Clones of an `OutputBuffer` share the same buffer, so a clone can be given to
+/// [`EvaluatorBuilder::output`] while the original is used to read the output.
+#[derive(Clone, Default)]
+pub struct OutputBuffer {
+    buf: Rc<RefCell<Vec<u8>>>,
+}
+
+impl OutputBuffer {
+    /// Creates an empty buffer
+    pub fn new() -> OutputBuffer {
+        OutputBuffer::default()
+    }
+
+    /// Returns the output collected so far and clears the buffer
+    ///
+    /// Invalid UTF-8 is replaced with `U+FFFD`.
+    pub fn take(&self) -> String {
+        let buf = std::mem::take(&mut *self.buf.borrow_mut());
+        String::from_utf8_lossy(&buf).into_owned()
+    }
+
+    /// Returns the bytes collected so far and clears the buffer
+    pub fn take_bytes(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.buf.borrow_mut())
+    }
+}
+
+impl Write for OutputBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+type SystemFn1 = Box<dyn Fn(&BQNValue) -> Result<BQNValue>>;
+type SystemFn2 = Box<dyn Fn(&BQNValue, &BQNValue) -> Result<BQNValue>>;
+
+enum SystemValue {
+    Value(BQNValue),
+    // Namespaces and functions are created when building the evaluator so that errors in field
+    // names and from CBQN can be reported from `build`
+    Namespace(Vec<(String, BQNValue)>),
+    Fn1(SystemFn1),
+    Fn2(SystemFn2),
+}
+
+/// An evaluator for BQN code, created with [`EvaluatorBuilder`]
+pub struct Evaluator {
+    rebqn: BQNValue,
+}
+
+impl Evaluator {
+    /// Evaluates BQN code
+    pub fn eval(&self, bqn: &str) -> Result<BQNValue> {
+        self.rebqn
+            .call1(&BQNValue::from(bqn))
+            .map_err(|e| e.with_source(bqn))
+    }
+
+    /// Evaluates BQN code with `•args` set to `args`
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQNValue, Error, EvaluatorBuilder};
+    /// let evaluator = EvaluatorBuilder::new().build()?;
+    /// let args = [BQNValue::from(1.0), BQNValue::from(2.0)];
+    /// assert_eq!(evaluator.eval_with_args("+´•args", &args)?.to_f64()?, 3.0);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn eval_with_args(&self, bqn: &str, args: &[BQNValue]) -> Result<BQNValue> {
+        EVAL_WITH_ARGS
+            .call2(
+                &BQNValue::from([self.rebqn.clone(), BQNValue::from(args)]),
+                &BQNValue::from(bqn),
+            )
+            .map_err(|e| e.with_source(bqn))
+    }
+}
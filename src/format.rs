@@ -0,0 +1,282 @@
+use crate::{
+    cache::{FMT, REPR},
+    BQNType, BQNValue, Compiled, Result,
+};
+use std::fmt;
+
+static PREFIX: Compiled = Compiled::new("{w‿l 𝕊 x: 0==x ? x ; ((≢x)⌊w∾˜l⥊˜1-˜=x)↑x}");
+
+/// How [`BQNValue::format`] formats values
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FormatStyle {
+    /// `•Fmt`, the format used by the BQN REPL
+    #[default]
+    Fmt,
+    /// `•Repr`, BQN code that evaluates to the value. Values that `•Repr` can't represent, such
+    /// as functions, are formatted with [`FormatStyle::Compact`].
+    Repr,
+    /// One line formatted on the Rust side, such as `2‿2⥊⟨ 1 2 3 4 ⟩`. Only the elements that
+    /// fit in [`FormatOptions::max_width`] are read from CBQN.
+    Compact,
+}
+
+/// Options for [`BQNValue::format`]
+///
+/// # Examples
+/// ```
+/// # use cbqn::{eval, Error, FormatOptions, FormatStyle};
+/// let v = eval("↕1e6")?;
+/// let options = FormatOptions::new().style(FormatStyle::Compact).max_width(16);
+/// assert_eq!(v.format(options).to_string(), "⟨ 0 1 2 3 4 5 6…");
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FormatOptions {
+    style: FormatStyle,
+    max_width: Option<usize>,
+    max_lines: Option<usize>,
+}
+
+impl FormatOptions {
+    /// Creates options that format with `•Fmt` without truncation
+    pub fn new() -> FormatOptions {
+        FormatOptions::default()
+    }
+
+    /// Sets the formatting style
+    pub fn style(mut self, style: FormatStyle) -> FormatOptions {
+        self.style = style;
+        self
+    }
+
+    /// Truncates lines longer than `width` characters, ending them with `…`
+    ///
+    /// With [`FormatStyle::Fmt`] and [`FormatStyle::Repr`], arrays are cut to the part that can
+    /// be shown before formatting, so the alignment of columns only depends on the shown part.
+    pub fn max_width(mut self, width: usize) -> FormatOptions {
+        self.max_width = Some(width);
+        self
+    }
+
+    /// Truncates the output to `lines` lines, followed by a line with `…`
+    pub fn max_lines(mut self, lines: usize) -> FormatOptions {
+        self.max_lines = Some(lines);
+        self
+    }
+}
+
+/// A `BQNValue` formatted with [`FormatOptions`]
+///
+/// Created with [`BQNValue::format`].
+pub struct Formatted<'a> {
+    value: &'a BQNValue,
+    options: FormatOptions,
+}
+
+impl BQNValue {
+    /// Formats `BQNValue` with `options`
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, eval, Error, FormatOptions, FormatStyle};
+    /// let v = BQN!("⟨1, \"ab\"⟩")?;
+    /// let repr = FormatOptions::new().style(FormatStyle::Repr);
+    /// assert_eq!(v.format(repr).to_string(), "⟨1,\"ab\"⟩");
+    /// assert_eq!(format!("{v}"), "⟨ 1 \"ab\" ⟩");
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn format(&self, options: FormatOptions) -> Formatted<'_> {
+        Formatted {
+            value: self,
+            options,
+        }
+    }
+}
+
+impl fmt::Display for Formatted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let FormatOptions {
+            style,
+            max_width,
+            max_lines,
+        } = self.options;
+        let formatted = match style {
+            FormatStyle::Fmt => format_with(&FMT, self.value, max_width, max_lines),
+            FormatStyle::Repr => format_with(&REPR, self.value, max_width, max_lines)
+                .or_else(|_| compact(self.value, max_width)),
+            FormatStyle::Compact => compact(self.value, max_width),
+        };
+        match formatted {
+            Ok(s) => f.write_str(&truncate(&s, max_width, max_lines)),
+            Err(e) => write!(f, "<BQNValue: {e}>"),
+        }
+    }
+}
+
+/// Formats `BQNValue` with `•Fmt`
+///
+/// See [`BQNValue::format`] for other formats and truncating the output. As the inherent
+/// [`BQNValue::to_string`] converts BQN strings into Rust strings, use `format!("{value}")` to
+/// get the formatted value as a string.
+impl fmt::Display for BQNValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.format(FormatOptions::new()), f)
+    }
+}
+
+fn format_with(
+    func: &Compiled,
+    value: &BQNValue,
+    max_width: Option<usize>,
+    max_lines: Option<usize>,
+) -> Result<String> {
+    if max_width.is_none() && max_lines.is_none() {
+        return func.call1(value)?.to_string();
+    }
+    // Every element along the last axis takes at least one column and every cell along the other
+    // axes at least one line, so the rest of the array wouldn't be shown
+    let limit = |n: Option<usize>| n.map_or(f64::INFINITY, |n| n as f64 + 1.0);
+    let limits = BQNValue::from([limit(max_width), limit(max_lines)]);
+    func.call1(&PREFIX.call2(&limits, value)?)?.to_string()
+}
+
+fn truncate(s: &str, max_width: Option<usize>, max_lines: Option<usize>) -> String {
+    let lines = s.split('\n').collect::<Vec<_>>();
+    let shown = max_lines.map_or(lines.len(), |n| n.min(lines.len()));
+    let mut ret = lines[..shown]
+        .iter()
+        .map(|line| match max_width {
+            Some(w) if line.chars().count() > w => {
+                let mut line = line.chars().take(w.saturating_sub(1)).collect::<String>();
+                line.push('…');
+                line
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if shown < lines.len() {
+        ret.push_str("\n…");
+    }
+    ret
+}
+
+fn compact(value: &BQNValue, max_width: Option<usize>) -> Result<String> {
+    let _l = crate::LOCK.lock();
+    let mut c = Compact {
+        out: String::new(),
+        len: 0,
+        // One character more than fits, so that the output gets truncated
+        limit: max_width.map_or(usize::MAX, |w| w + 1),
+    };
+    c.value(value)?;
+    Ok(c.out)
+}
+
+// Writes values until `limit` characters have been written
+struct Compact {
+    out: String,
+    len: usize,
+    limit: usize,
+}
+
+impl Compact {
+    fn full(&self) -> bool {
+        self.len >= self.limit
+    }
+
+    fn push(&mut self, s: &str) {
+        self.out.push_str(s);
+        self.len += s.chars().count();
+    }
+
+    fn value(&mut self, v: &BQNValue) -> Result<()> {
+        if self.full() {
+            return Ok(());
+        }
+        match v.try_bqn_type()? {
+            BQNType::Number => self.push(&number(v.to_f64()?)),
+            BQNType::Character => match v.to_u32()? {
+                0 => self.push("@"),
+                c => {
+                    self.push("'");
+                    self.char(c);
+                    self.push("'");
+                }
+            },
+            BQNType::Array => self.array(v)?,
+            BQNType::Function => self.push("*function*"),
+            BQNType::Mod1 => self.push("*1-modifier*"),
+            BQNType::Mod2 => self.push("*2-modifier*"),
+            BQNType::Namespace => self.push("*namespace*"),
+        }
+        Ok(())
+    }
+
+    // Control characters are shown as control pictures to keep the output on one line
+    fn char(&mut self, c: u32) {
+        let c = match c {
+            0..=0x1f => char::from_u32(0x2400 + c),
+            c => char::from_u32(c),
+        };
+        self.push(c.unwrap_or('�').encode_utf8(&mut [0; 4]));
+    }
+
+    fn array(&mut self, v: &BQNValue) -> Result<()> {
+        let shape = v.try_shape()?;
+        let b = v.bound()?;
+        if shape.len() == 1 && v.known_char_arr()? {
+            self.push("\"");
+            for i in 0..b {
+                if self.full() {
+                    return Ok(());
+                }
+                match v.element(i)?.to_u32()? {
+                    0x22 => self.push("\"\""),
+                    c => self.char(c),
+                }
+            }
+            self.push("\"");
+            return Ok(());
+        }
+        match shape.len() {
+            0 => {
+                self.push("<");
+                return self.value(&v.element(0)?);
+            }
+            1 => {}
+            _ => {
+                let shape = shape.iter().map(usize::to_string).collect::<Vec<_>>();
+                self.push(&shape.join("‿"));
+                self.push("⥊");
+            }
+        }
+        if b == 0 {
+            self.push("⟨⟩");
+            return Ok(());
+        }
+        self.push("⟨");
+        for i in 0..b {
+            if self.full() {
+                return Ok(());
+            }
+            self.push(" ");
+            self.value(&v.element(i)?)?;
+        }
+        self.push(" ⟩");
+        Ok(())
+    }
+}
+
+// Formats numbers like BQN does, e.g. `¯1.5`, `1e¯7` and `∞`
+fn number(f: f64) -> String {
+    if f.is_nan() {
+        return "NaN".into();
+    }
+    if f.is_infinite() {
+        return if f > 0.0 { "∞" } else { "¯∞" }.into();
+    }
+    let s = format!("{f:?}");
+    let s = s.strip_suffix(".0").unwrap_or(&s);
+    s.replace('-', "¯")
+}
@@ -0,0 +1,374 @@
+use crate::{backend::*, BQNType, BQNValue, Compiled, Error, Result, LOCK};
+use std::iter::FusedIterator;
+use std::vec;
+
+// Number of elements read from CBQN at a time by the typed iterators
+const CHUNK_SIZE: usize = 4096;
+
+static SELECT: Compiled = Compiled::new("⊏");
+static SLICE: Compiled = Compiled::new("{s‿n 𝕊 𝕩: n↑s↓⥊𝕩}");
+
+impl BQNValue {
+    /// Returns an iterator over the elements of an array in deshaped order, same as `⥊a`
+    ///
+    /// Elements are picked from CBQN one at a time, so the array isn't copied into a `Vec` like
+    /// with [`BQNValue::to_bqnvalue_vec`]. Returns an error if the value isn't an array. The
+    /// iterator yields an error for an element that the backend fails to read.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, eval, Error};
+    /// let v = BQN!("⟨1, \"ab\", 'c'⟩")?;
+    /// let types = v.iter()?.map(|e| e.map(|e| e.bqn_type())).collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(types.len(), 3);
+    /// let last = v.iter()?.next_back().unwrap()?;
+    /// assert_eq!(last.to_char()?, Some('c'));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn iter(&self) -> Result<Iter<'_>> {
+        let _l = LOCK.lock();
+        if self.try_bqn_type()? != BQNType::Array {
+            return Err(Error::InvalidType("value isn't an array".into()));
+        }
+        Ok(Iter {
+            value: self,
+            front: 0,
+            back: self.bound()?,
+        })
+    }
+
+    /// Returns an iterator over the major cells of an array, same as `<˘a`
+    ///
+    /// The cells have rank one less than the array, so the cells of a list are enclosed elements.
+    /// Returns an error if the value isn't an array of rank 1 or more. The iterator yields an
+    /// error for a cell that the backend fails to select.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, eval, Error};
+    /// let m = BQN!("3‿2⥊↕6")?;
+    /// let sums = m
+    ///     .major_cells()?
+    ///     .map(|row| row?.to_f64_vec().map(|r| r.iter().sum()))
+    ///     .collect::<Result<Vec<f64>, _>>()?;
+    /// assert_eq!(sums, [1.0, 5.0, 9.0]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn major_cells(&self) -> Result<MajorCells<'_>> {
+        let _l = LOCK.lock();
+        if self.try_bqn_type()? != BQNType::Array {
+            return Err(Error::InvalidType("value isn't an array".into()));
+        }
+        let len = *self
+            .try_shape()?
+            .first()
+            .ok_or_else(|| Error::InvalidType("value is a unit array".into()))?;
+        Ok(MajorCells {
+            value: self,
+            front: 0,
+            back: len,
+        })
+    }
+
+    /// Returns an iterator over the elements of a numeric array as `f64`s
+    ///
+    /// Elements are read from CBQN in chunks with the read function of the array's element type,
+    /// so a long array isn't copied at once. Returns an error if the value isn't an array of
+    /// numbers. If the backend fails to read a chunk, the iterator yields the error and ends.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, eval, Error};
+    /// let v = BQN!("↕1e6")?;
+    /// let first = v.iter_f64()?.take(4).collect::<Result<Vec<f64>, _>>()?;
+    /// assert_eq!(first, [0.0, 1.0, 2.0, 3.0]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn iter_f64(&self) -> Result<IterF64<'_>> {
+        let _l = LOCK.lock();
+        let len = self.get_numeric_array_bounds()?;
+        Ok(IterF64 {
+            chunks: Chunks::new(self, len, read_f64s),
+        })
+    }
+
+    /// Returns an iterator over the elements of a character array as `char`s
+    ///
+    /// Elements are read from CBQN in chunks like in [`BQNValue::iter_f64`]. Code points that
+    /// aren't Unicode scalar values are skipped, like in [`BQNValue::to_char_vec`]. Returns an
+    /// error if the value isn't an array of characters. If the backend fails to read a chunk, the
+    /// iterator yields the error and ends.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, eval, Error};
+    /// let v = BQN!("\"hello\"")?;
+    /// assert_eq!(v.iter_chars()?.rev().collect::<Result<String, _>>()?, "olleh");
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn iter_chars(&self) -> Result<IterChars<'_>> {
+        let _l = LOCK.lock();
+        let len = self.get_character_array_bounds()?;
+        Ok(IterChars {
+            chunks: Chunks::new(self, len, read_c32s),
+        })
+    }
+}
+
+/// An iterator over the elements of an array
+///
+/// Created with [`BQNValue::iter`].
+pub struct Iter<'a> {
+    value: &'a BQNValue,
+    front: usize,
+    back: usize,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Result<BQNValue>;
+
+    fn next(&mut self) -> Option<Result<BQNValue>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.value.element(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Result<BQNValue>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.value.element(self.back))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+/// An iterator over the major cells of an array
+///
+/// Created with [`BQNValue::major_cells`].
+pub struct MajorCells<'a> {
+    value: &'a BQNValue,
+    front: usize,
+    back: usize,
+}
+
+impl MajorCells<'_> {
+    fn cell(&self, i: usize) -> Result<BQNValue> {
+        SELECT.call2(&BQNValue::try_from_f64(i as f64)?, self.value)
+    }
+}
+
+impl Iterator for MajorCells<'_> {
+    type Item = Result<BQNValue>;
+
+    fn next(&mut self) -> Option<Result<BQNValue>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.cell(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for MajorCells<'_> {
+    fn next_back(&mut self) -> Option<Result<BQNValue>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.cell(self.back))
+    }
+}
+
+impl ExactSizeIterator for MajorCells<'_> {}
+
+impl FusedIterator for MajorCells<'_> {}
+
+/// An iterator over the elements of a numeric array as `f64`s
+///
+/// Created with [`BQNValue::iter_f64`].
+pub struct IterF64<'a> {
+    chunks: Chunks<'a, f64>,
+}
+
+impl Iterator for IterF64<'_> {
+    type Item = Result<f64>;
+
+    fn next(&mut self) -> Option<Result<f64>> {
+        self.chunks.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IterF64<'_> {
+    fn next_back(&mut self) -> Option<Result<f64>> {
+        self.chunks.next_back()
+    }
+}
+
+impl FusedIterator for IterF64<'_> {}
+
+/// An iterator over the elements of a character array as `char`s
+///
+/// Created with [`BQNValue::iter_chars`].
+pub struct IterChars<'a> {
+    chunks: Chunks<'a, u32>,
+}
+
+impl Iterator for IterChars<'_> {
+    type Item = Result<char>;
+
+    fn next(&mut self) -> Option<Result<char>> {
+        self.chunks.find_map(|c| c.map(char::from_u32).transpose())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.chunks.size_hint().1)
+    }
+}
+
+impl DoubleEndedIterator for IterChars<'_> {
+    fn next_back(&mut self) -> Option<Result<char>> {
+        self.chunks
+            .by_ref()
+            .rev()
+            .find_map(|c| c.map(char::from_u32).transpose())
+    }
+}
+
+impl FusedIterator for IterChars<'_> {}
+
+// Reads the elements of an array from both ends, `CHUNK_SIZE` elements at a time. The remaining
+// elements are `front..back`, and the elements that have been read but not yielded are buffered.
+struct Chunks<'a, T> {
+    value: &'a BQNValue,
+    len: usize,
+    read: fn(&BQNValue) -> Result<Vec<T>>,
+    front: usize,
+    back: usize,
+    front_buf: vec::IntoIter<T>,
+    back_buf: vec::IntoIter<T>,
+}
+
+impl<'a, T> Chunks<'a, T> {
+    fn new(value: &'a BQNValue, len: usize, read: fn(&BQNValue) -> Result<Vec<T>>) -> Self {
+        Chunks {
+            value,
+            len,
+            read,
+            front: 0,
+            back: len,
+            front_buf: Vec::new().into_iter(),
+            back_buf: Vec::new().into_iter(),
+        }
+    }
+
+    fn read_chunk(&self, start: usize, end: usize) -> Result<Vec<T>> {
+        let _l = LOCK.lock();
+        if end - start == self.len {
+            return (self.read)(self.value);
+        }
+        let range = BQNValue::try_from_f64_slice(&[start as f64, (end - start) as f64])?;
+        (self.read)(&SLICE.call2(&range, self.value)?)
+    }
+
+    // Ends the iteration after an error
+    fn fail(&mut self, e: Error) -> Option<Result<T>> {
+        self.front = self.back;
+        self.front_buf = Vec::new().into_iter();
+        self.back_buf = Vec::new().into_iter();
+        Some(Err(e))
+    }
+}
+
+impl<T> Iterator for Chunks<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            if let Some(v) = self.front_buf.next() {
+                return Some(Ok(v));
+            }
+            if self.front == self.back {
+                return self.back_buf.next().map(Ok);
+            }
+            let end = self.back.min(self.front + CHUNK_SIZE);
+            match self.read_chunk(self.front, end) {
+                Ok(chunk) => {
+                    self.front_buf = chunk.into_iter();
+                    self.front = end;
+                }
+                Err(e) => return self.fail(e),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // An error ends the iteration, so only the buffered elements are certain
+        let buffered = self.front_buf.len() + self.back_buf.len();
+        (buffered, Some(buffered + self.back - self.front))
+    }
+}
+
+impl<T> DoubleEndedIterator for Chunks<'_, T> {
+    fn next_back(&mut self) -> Option<Result<T>> {
+        loop {
+            if let Some(v) = self.back_buf.next_back() {
+                return Some(Ok(v));
+            }
+            if self.front == self.back {
+                return self.front_buf.next_back().map(Ok);
+            }
+            let start = self.front.max(self.back.saturating_sub(CHUNK_SIZE));
+            match self.read_chunk(start, self.back) {
+                Ok(chunk) => {
+                    self.back_buf = chunk.into_iter();
+                    self.back = start;
+                }
+                Err(e) => return self.fail(e),
+            }
+        }
+    }
+}
+
+impl<T> FusedIterator for Chunks<'_, T> {}
+
+// Reads a numeric array with the read function of its element type
+fn read_f64s(value: &BQNValue) -> Result<Vec<f64>> {
+    #![allow(non_upper_case_globals)]
+    let b = value.bound()?;
+    Ok(match value.direct_arr_type()? {
+        BQNElType_elt_i8 => widen(value.read_arr(b, bqn_readI8Arr)?),
+        BQNElType_elt_i16 => widen(value.read_arr(b, bqn_readI16Arr)?),
+        BQNElType_elt_i32 => widen(value.read_arr(b, bqn_readI32Arr)?),
+        _ => value.read_arr(b, bqn_readF64Arr)?,
+    })
+}
+
+fn widen<T: Into<f64>>(v: Vec<T>) -> Vec<f64> {
+    v.into_iter().map(Into::into).collect()
+}
+
+fn read_c32s(value: &BQNValue) -> Result<Vec<u32>> {
+    value.read_arr(value.bound()?, bqn_readC32Arr)
+}
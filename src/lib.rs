@@ -9,7 +9,9 @@
 //! # Usage
 //!
 //! Simple expressions can be run with the `BQN!` convenience macro. For more advanced use, the
-//! methods of `BQNValue` provide the necessary functionality.
+//! methods of `BQNValue` provide the necessary functionality. Code that needs to keep variables
+//! between evaluations can be run in a [`Session`], and code that needs custom primitives or
+//! system values in an evaluator created with [`EvaluatorBuilder`].
 //!
 //! # Examples using the BQN! macro
 //! ```
@@ -47,6 +49,16 @@
 //!
 //! ```
 //! # use cbqn::{BQNValue, eval, Error};
+//! let a = BQNValue::from([1.0, 2.0, 3.0]);
+//! let b = BQNValue::from(10);
+//! let sum = (&a + &b)?;
+//! assert_eq!((&sum * &a)?.to_f64_vec()?, vec![11.0, 24.0, 39.0]);
+//! assert_eq!((-&sum)?.to_f64_vec()?, vec![-11.0, -12.0, -13.0]);
+//! # Ok::<(), Error>(())
+//! ```
+//!
+//! ```
+//! # use cbqn::{BQNValue, eval, Error};
 //! let sum = eval("1+1")?;
 //! assert_eq!(sum.to_f64()?, 2.0);
 //! # Ok::<(), Error>(())
@@ -81,6 +93,28 @@
 //! assert_eq!(result.to_f64()?, 6.0);
 //! # Ok::<(), Error>(())
 //! ```
+//!
+//! # Optional features
+//!
+//! * `ndarray`: Conversions between `BQNValue` and `ndarray::ArrayD` that preserve the shape of
+//!   the array
+//!
+//! ```
+//! # #[cfg(feature = "ndarray")]
+//! # {
+//! # use cbqn::{BQN, BQNValue, eval, Error};
+//! use ndarray::{arr2, ArrayD};
+//! let matrix = BQNValue::from(arr2(&[[1.0, 2.0], [3.0, 4.0]]).into_dyn());
+//! let transposed = ArrayD::<f64>::try_from(&BQN!("⍉", matrix)?)?;
+//! assert_eq!(transposed, arr2(&[[1.0, 3.0], [2.0, 4.0]]).into_dyn());
+//! # }
+//! # Ok::<(), cbqn::Error>(())
+//! ```
+//!
+//! * `serde`: [`to_value`] and [`from_value`] for converting between BQN values and types that
+//!   implement `Serialize` and `Deserialize`. Structs are represented as namespaces.
+//! * `derive`: `#[derive(ToBQN, FromBQN)]` for structs and enums. See [`ToBQN`] for the
+//!   representation of the derived types.
 
 // Clippy outputs false positives because some of the casts and conversions are needed in the wasi
 // backend, but not in the native backend
@@ -90,24 +124,75 @@
 
 mod backend;
 use backend::*;
+use namespace::{make_namespace, namespace_fields, normalize_name};
 
 use parking_lot::ReentrantMutex;
 use std::cell::RefCell;
-use std::sync::{LazyLock, Once};
-use std::{fmt, mem};
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::LazyLock;
+use std::{fmt, mem, thread};
 
 #[cfg(test)]
 mod tests;
+// Lets the derive macros refer to `::cbqn` in the tests of this crate
+#[cfg(all(test, feature = "derive"))]
+extern crate self as cbqn;
 
 mod bqntype;
+mod cache;
+mod compare;
 mod conversions;
+mod error;
+mod evaluator;
+mod format;
+mod iter;
 mod macros;
+mod namespace;
+#[cfg(feature = "ndarray")]
+mod ndarray;
+mod ops;
+mod path;
+#[cfg(feature = "serde")]
+mod serde;
+mod session;
+mod traits;
+
+#[cfg(feature = "serde")]
+pub use crate::serde::{from_value, to_value};
+/// Another name for the `FromBQN` derive macro
+#[cfg(feature = "derive")]
+pub use cbqn_derive::FromBQN as FromBQNValue;
+#[cfg(feature = "derive")]
+pub use cbqn_derive::{FromBQN, ToBQN};
+pub use error::{BQNError, Span};
+/// Another name for [`FromBQN`], the counterpart of `TryFrom<&BQNValue>`
+pub use traits::FromBQN as FromBQNValue;
+pub use traits::{FromBQN, ToBQN};
 
+// Used by the code generated by the derive macros
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::namespace::{make_namespace, namespace_fields, normalize_name};
+    pub use crate::traits::{
+        enum_variant, expect_null, in_field, in_type, list_elements, missing_field, take_field,
+        unknown_variant,
+    };
+}
 pub use backend::Error;
 pub use bqntype::BQNType;
+pub use cache::{eval_cached, Compiled};
+pub use evaluator::{Evaluator, EvaluatorBuilder, OutputBuffer};
+pub use format::{FormatOptions, FormatStyle, Formatted};
+pub use iter::{Iter, IterChars, IterF64, MajorCells};
+pub use session::{ReplMode, Session};
 
 static LOCK: LazyLock<ReentrantMutex<()>> = LazyLock::new(|| ReentrantMutex::new(()));
-static INIT: Once = Once::new();
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+static APPLY_MOD1: Compiled = Compiled::new("{_m←𝕨 ⋄ 𝕩 _m}");
+static APPLY_MOD2: Compiled = Compiled::new("{_m_←𝕨 ⋄ f‿g←𝕩 ⋄ f _m_ g}");
 
 /// Represents a BQN value
 pub struct BQNValue {
@@ -128,72 +213,177 @@ impl BQNValue {
     /// BQN!('a', "-", BQNValue::null()).unwrap();
     /// ```
     pub fn null() -> BQNValue {
-        let _l = LOCK.lock();
-        crate::INIT.call_once(|| bqn_init().unwrap());
-        BQNValue::new(bqn_makeChar(0).unwrap())
+        BQNValue::try_null().unwrap()
+    }
+
+    /// Constructs a BQN null value `@`, returning an error if CBQN fails to allocate it
+    pub fn try_null() -> Result<BQNValue> {
+        BQNValue::try_from_char('\0')
     }
 
     /// Returns a boolean value indicating whether `field` exists in a BQN namespace
-    /// As CBQN requires the searched field name to be in a string with all lowercase letters, this
-    /// function returns `false` if it is supplied with a `field` string that contains uppercase
-    /// characters.
+    ///
+    /// `field` is normalized like BQN normalizes names, by removing underscores and converting it
+    /// to lowercase, so `MaxDepth`, `max_depth` and `maxdepth` all refer to the same field.
+    /// Returns an error if `field` isn't a valid BQN name.
     pub fn has_field(&self, field: &str) -> Result<bool> {
         let _l = LOCK.lock();
-
-        if !BQNValue::is_valid_namespace_field(field) {
-            return Ok(false);
-        }
-
-        if self.bqn_type() != BQNType::Namespace {
+        let field = normalize_name(field)?;
+        if self.try_bqn_type()? != BQNType::Namespace {
             return Err(Error::InvalidType("value isn't a namespace".into()));
         }
-        bqn_hasField(self.value, BQNValue::from(field).value)
+        bqn_hasField(self.value, BQNValue::try_from_str(&field)?.value)
     }
 
     /// Returns `field` from a BQN namespace as `BQNValue`. Returns `None` if the field cannot be
     /// found.
-    /// As CBQN requires the searched field name to be in a string with all lowercase letters, this
-    /// function returns `None` if it is supplied with a `field` string that contains uppercase
-    /// characters.
+    ///
+    /// `field` is normalized like BQN normalizes names, by removing underscores and converting it
+    /// to lowercase, so `MaxDepth`, `max_depth` and `maxdepth` all refer to the same field.
+    /// Returns an error if `field` isn't a valid BQN name.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, eval, Error};
+    /// let ns = BQN!("{MaxDepth⇐3}")?;
+    /// assert!(ns.get_field("max_depth")?.is_some());
+    /// assert!(ns.get_field("maxdepth")?.is_some());
+    /// assert!(ns.get_field("max depth").is_err());
+    /// # Ok::<(), Error>(())
+    /// ```
     pub fn get_field(&self, field: &str) -> Result<Option<BQNValue>> {
         let _l = LOCK.lock();
-
-        if !BQNValue::is_valid_namespace_field(field) {
-            return Ok(None);
-        }
-
-        if self.bqn_type() != BQNType::Namespace {
+        let field = normalize_name(field)?;
+        if self.try_bqn_type()? != BQNType::Namespace {
             return Err(Error::InvalidType("value isn't a namespace".into()));
         }
-        let f = BQNValue::from(field);
-        Ok(if bqn_hasField(self.value, f.value).unwrap() {
+        let f = BQNValue::try_from_str(&field)?;
+        Ok(if bqn_hasField(self.value, f.value)? {
             Some(BQNValue::new(bqn_getField(self.value, f.value)?))
         } else {
             None
         })
     }
 
+    /// Creates a namespace from field names and values
+    ///
+    /// Names are normalized like BQN normalizes them, by removing underscores and converting them
+    /// to lowercase, so `max_depth` and `MaxDepth` both define the field `maxdepth`. Returns an
+    /// error if a name isn't a valid BQN name or if two names define the same field.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, eval, Error};
+    /// let ns = BQNValue::namespace_from([("max_depth", 3.into()), ("name", "tree".into())])?;
+    /// assert_eq!(BQN!("{𝕩.maxDepth}", ns)?.to_f64()?, 3.0);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn namespace_from<I, S>(fields: I) -> Result<BQNValue>
+    where
+        I: IntoIterator<Item = (S, BQNValue)>,
+        S: AsRef<str>,
+    {
+        make_namespace(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name.as_ref().to_owned(), value))
+                .collect(),
+        )
+    }
+
+    /// Returns the names and values of the fields exported by a BQN namespace
+    ///
+    /// The names are normalized, i.e. in lowercase and without underscores.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, eval, Error};
+    /// let ns = BQN!("{a⇐1 ⋄ b←2 ⋄ max_depth⇐3}")?;
+    /// let names = ns.fields()?.into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+    /// assert_eq!(names, ["a", "maxdepth"]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn fields(&self) -> Result<Vec<(String, BQNValue)>> {
+        namespace_fields(self)
+    }
+
     /// Calls `BQNValue` as a function with one argument
+    ///
+    /// Errors thrown by the function are returned as `Error::BQN`.
     pub fn call1(&self, x: &BQNValue) -> Result<BQNValue> {
         let _l = LOCK.lock();
-        Ok(BQNValue::new(bqn_call1(self.value, x.value)?))
+        backend_call1(self, x)
     }
 
     /// Calls `BQNValue` as a function with two arguments
+    ///
+    /// Errors thrown by the function are returned as `Error::BQN`.
     pub fn call2(&self, w: &BQNValue, x: &BQNValue) -> Result<BQNValue> {
         let _l = LOCK.lock();
-        Ok(BQNValue::new(bqn_call2(self.value, w.value, x.value)?))
+        backend_call2(self, w, x)
+    }
+
+    /// Applies `BQNValue` as a 1-modifier to operand `f`
+    ///
+    /// Returns the derived function. The operand can be any value, not only a function.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, eval};
+    /// let each = eval("¨").unwrap();
+    /// let neg = eval("-").unwrap();
+    /// let neg_each = each.apply_mod1(&neg).unwrap();
+    /// let ret = neg_each.call1(&[1, 2, 3].into()).unwrap();
+    /// assert_eq!(ret.to_f64_vec().unwrap(), vec![-1.0, -2.0, -3.0]);
+    /// ```
+    pub fn apply_mod1(&self, f: &BQNValue) -> Result<BQNValue> {
+        if self.try_bqn_type()? != BQNType::Mod1 {
+            return Err(Error::InvalidType("value isn't a 1-modifier".into()));
+        }
+        APPLY_MOD1.call2(self, f)
+    }
+
+    /// Applies `BQNValue` as a 2-modifier to operands `f` and `g`
+    ///
+    /// Returns the derived function. The operands can be any values, not only functions.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, eval};
+    /// let under = eval("{𝔾⁼𝔽𝔾𝕩}").unwrap();
+    /// let neg = eval("-").unwrap();
+    /// let rev = eval("⌽").unwrap();
+    /// let f = under.apply_mod2(&neg, &rev).unwrap();
+    /// let ret = f.call1(&[1, 2, 3].into()).unwrap();
+    /// assert_eq!(ret.to_f64_vec().unwrap(), vec![-1.0, -2.0, -3.0]);
+    /// ```
+    pub fn apply_mod2(&self, f: &BQNValue, g: &BQNValue) -> Result<BQNValue> {
+        if self.try_bqn_type()? != BQNType::Mod2 {
+            return Err(Error::InvalidType("value isn't a 2-modifier".into()));
+        }
+        let operands = BQNValue::try_from_values(vec![f.try_clone()?, g.try_clone()?])?;
+        APPLY_MOD2.call2(self, &operands)
     }
 
     /// Returns the BQN type of the BQNValue
+    ///
+    /// # Panics
+    ///
+    /// Panics if the backend fails. See [`BQNValue::try_bqn_type`].
     pub fn bqn_type(&self) -> BQNType {
-        BQNType::try_from(bqn_type(self.value).unwrap()).expect("expected to handle all types")
+        self.try_bqn_type().unwrap()
+    }
+
+    /// Returns the BQN type of the BQNValue, or an error if the backend fails
+    pub fn try_bqn_type(&self) -> Result<BQNType> {
+        let _l = LOCK.lock();
+        to_bqn_type(bqn_type(self.value)?)
     }
 
     /// Converts `BQNValue` into `f64`
     pub fn to_f64(&self) -> Result<f64> {
         let _l = LOCK.lock();
-        if self.bqn_type() != BQNType::Number {
+        if self.try_bqn_type()? != BQNType::Number {
             return Err(Error::InvalidType("value isn't a number".into()));
         }
         bqn_readF64(self.value)
@@ -205,7 +395,7 @@ impl BQNValue {
     /// characters that are not Unicode scalar values.
     pub fn to_char(&self) -> Result<Option<char>> {
         let _l = LOCK.lock();
-        if self.bqn_type() != BQNType::Character {
+        if self.try_bqn_type()? != BQNType::Character {
             return Err(Error::InvalidType("value isn't a character".into()));
         }
         Ok(char::from_u32(bqn_readChar(self.value)?))
@@ -217,7 +407,7 @@ impl BQNValue {
     /// be converted into a Rust type `u32` using this function.
     pub fn to_u32(&self) -> Result<u32> {
         let _l = LOCK.lock();
-        if self.bqn_type() != BQNType::Character {
+        if self.try_bqn_type()? != BQNType::Character {
             return Err(Error::InvalidType("value isn't a character".into()));
         }
         bqn_readChar(self.value)
@@ -235,20 +425,70 @@ impl BQNValue {
             // anyway
             ret.set_len(b)
         };
-        bqn_readF64Arr(self.value, &mut ret).unwrap();
+        bqn_readF64Arr(self.value, &mut ret)?;
         drop(l);
 
         Ok(ret)
     }
 
+    /// Converts `BQNValue` into a vector of `i32`s
+    ///
+    /// Returns an error if any of the elements isn't an integer in the range of `i32`.
+    pub fn to_i32_vec(&self) -> Result<Vec<i32>> {
+        self.to_int_vec("i32")
+    }
+
+    /// Converts `BQNValue` into a vector of `i16`s
+    ///
+    /// Returns an error if any of the elements isn't an integer in the range of `i16`.
+    pub fn to_i16_vec(&self) -> Result<Vec<i16>> {
+        self.to_int_vec("i16")
+    }
+
+    /// Converts `BQNValue` into a vector of `i8`s
+    ///
+    /// Returns an error if any of the elements isn't an integer in the range of `i8`.
+    pub fn to_i8_vec(&self) -> Result<Vec<i8>> {
+        self.to_int_vec("i8")
+    }
+
+    /// Converts `BQNValue` into a vector of `u8`s
+    ///
+    /// Returns an error if any of the elements isn't an integer in the range of `u8`.
+    pub fn to_u8_vec(&self) -> Result<Vec<u8>> {
+        self.to_int_vec("u8")
+    }
+
+    /// Converts `BQNValue` into a vector of `bool`s
+    ///
+    /// Returns an error if any of the elements isn't `0` or `1`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, eval};
+    /// let mask = BQN!("3 < 1‿5‿2‿4").unwrap();
+    /// assert_eq!(mask.to_bool_vec().unwrap(), vec![false, true, false, true]);
+    /// assert!(BQN!("0‿1‿2").unwrap().to_bool_vec().is_err());
+    /// ```
+    pub fn to_bool_vec(&self) -> Result<Vec<bool>> {
+        self.to_int_vec::<u8>("bool")?
+            .into_iter()
+            .map(|b| match b {
+                0 => Ok(false),
+                1 => Ok(true),
+                _ => Err(Error::InvalidType("value isn't a bool array".into())),
+            })
+            .collect()
+    }
+
     /// Converts `BQNValue` into a vector of `BQNValue`s
     pub fn to_bqnvalue_vec(&self) -> Result<Vec<BQNValue>> {
         let l = LOCK.lock();
-        if self.bqn_type() != BQNType::Array {
+        if self.try_bqn_type()? != BQNType::Array {
             return Err(Error::InvalidType("value isn't an object array".into()));
         }
 
-        let b = self.bound();
+        let b = self.bound()?;
         let mut objarr = Vec::with_capacity(b);
         #[allow(clippy::uninit_vec)]
         unsafe {
@@ -257,20 +497,107 @@ impl BQNValue {
             // anyway
             objarr.set_len(b)
         };
-        bqn_readObjArr(self.value, &mut objarr).unwrap();
+        bqn_readObjArr(self.value, &mut objarr)?;
         drop(l);
 
         Ok(objarr.into_iter().map(BQNValue::new).collect())
     }
 
+    /// Returns the element at `index` of the deshaped array, same as `index⊑⥊a`
+    ///
+    /// Only the picked element is read from CBQN, unlike with [`BQNValue::to_bqnvalue_vec`].
+    /// Returns `Error::IndexOutOfBounds` if the array has `index` or fewer elements. See
+    /// [`BQNValue::get`] for indexing multidimensional arrays and for an `Option` when the index
+    /// is out of bounds.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, eval, Error};
+    /// let v = BQN!("2‿2⥊\"abcd\"")?;
+    /// assert_eq!(v.pick(2)?.to_char()?, Some('c'));
+    /// assert!(matches!(v.pick(4), Err(Error::IndexOutOfBounds(_))));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn pick(&self, index: usize) -> Result<BQNValue> {
+        let _l = LOCK.lock();
+        if self.try_bqn_type()? != BQNType::Array {
+            return Err(Error::InvalidType("value isn't an array".into()));
+        }
+        let b = self.bound()?;
+        if index >= b {
+            return Err(Error::IndexOutOfBounds(format!(
+                "index {index} in an array of {b} elements"
+            )));
+        }
+        self.element(index)
+    }
+
+    /// Returns the element at `index`, one number per axis, same as `index⊑a`
+    ///
+    /// Returns `None` if the index is out of bounds along any axis, and an error if the value
+    /// isn't an array or the length of `index` isn't the rank of the array. Only the element is
+    /// read from CBQN.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, eval, Error};
+    /// let v = BQN!("3‿2⥊↕6")?;
+    /// assert_eq!(v.get(&[2, 1])?.unwrap().to_f64()?, 5.0);
+    /// assert!(v.get(&[1, 2])?.is_none());
+    /// assert!(v.get(&[1]).is_err());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get(&self, index: &[usize]) -> Result<Option<BQNValue>> {
+        let _l = LOCK.lock();
+        if self.try_bqn_type()? != BQNType::Array {
+            return Err(Error::InvalidType("value isn't an array".into()));
+        }
+        let shape = self.try_shape()?;
+        if index.len() != shape.len() {
+            return Err(Error::InvalidType(format!(
+                "index of length {} for an array of rank {}",
+                index.len(),
+                shape.len()
+            )));
+        }
+        let mut i = 0;
+        for (&n, &len) in index.iter().zip(&shape) {
+            if n >= len {
+                return Ok(None);
+            }
+            i = i * len + n;
+        }
+        self.element(i).map(Some)
+    }
+
     /// Returns the rank of `BQNValue`, same as `=a`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the backend fails. See [`BQNValue::try_rank`].
     pub fn rank(&self) -> usize {
-        bqn_rank(self.value).unwrap()
+        self.try_rank().unwrap()
+    }
+
+    /// Returns the rank of `BQNValue`, same as `=a`, or an error if the backend fails
+    pub fn try_rank(&self) -> Result<usize> {
+        let _l = LOCK.lock();
+        bqn_rank(self.value)
     }
 
     /// Returns the shape of `BQNValue`, same as `≢a`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the backend fails. See [`BQNValue::try_shape`].
     pub fn shape(&self) -> Vec<usize> {
-        let rank = self.rank();
+        self.try_shape().unwrap()
+    }
+
+    /// Returns the shape of `BQNValue`, same as `≢a`, or an error if the backend fails
+    pub fn try_shape(&self) -> Result<Vec<usize>> {
+        let _l = LOCK.lock();
+        let rank = self.try_rank()?;
         let mut shape = Vec::with_capacity(rank);
         #[allow(clippy::uninit_vec)]
         unsafe {
@@ -280,16 +607,61 @@ impl BQNValue {
             shape.set_len(rank)
         };
 
-        bqn_shape(self.value, &mut shape).unwrap();
-        shape
+        bqn_shape(self.value, &mut shape)?;
+        Ok(shape)
     }
 
-    fn is_valid_namespace_field(field: &str) -> bool {
-        // CBQN requires the string to be all lowercase and contain no underscores
-        field.chars().all(|c| c.is_lowercase() && c != '_')
+    // Reads integer arrays using the narrowest read function that can hold the elements. Arrays
+    // that aren't known to contain integers are read as f64s and checked for integrality.
+    fn to_int_vec<T>(&self, type_name: &str) -> Result<Vec<T>>
+    where
+        T: TryFrom<i8> + TryFrom<i16> + TryFrom<i32> + TryFrom<i64>,
+    {
+        #![allow(non_upper_case_globals)]
+        fn convert<S, T: TryFrom<S>>(src: Vec<S>, type_name: &str) -> Result<Vec<T>> {
+            src.into_iter()
+                .map(|v| {
+                    T::try_from(v)
+                        .map_err(|_| Error::InvalidType(format!("value isn't a {type_name} array")))
+                })
+                .collect()
+        }
+
+        let _l = LOCK.lock();
+        let b = self.get_numeric_array_bounds()?;
+        match self.direct_arr_type()? {
+            BQNElType_elt_i8 => convert(self.read_arr(b, bqn_readI8Arr)?, type_name),
+            BQNElType_elt_i16 => convert(self.read_arr(b, bqn_readI16Arr)?, type_name),
+            BQNElType_elt_i32 => convert(self.read_arr(b, bqn_readI32Arr)?, type_name),
+            _ => self
+                .read_arr(b, bqn_readF64Arr)?
+                .into_iter()
+                .map(|f| {
+                    // i64::MAX as f64 rounds up to 2^63, which is out of range
+                    if f.fract() == 0.0 && f >= i64::MIN as f64 && f < i64::MAX as f64 {
+                        T::try_from(f as i64).ok()
+                    } else {
+                        None
+                    }
+                    .ok_or_else(|| Error::InvalidType(format!("value isn't a {type_name} array")))
+                })
+                .collect(),
+        }
     }
 
-    fn to_char_container<T: FromIterator<char>>(&self) -> Result<T> {
+    fn read_arr<T>(&self, b: usize, read: fn(BQNV, &mut [T]) -> Result<()>) -> Result<Vec<T>> {
+        let mut ret = Vec::with_capacity(b);
+        #[allow(clippy::uninit_vec)]
+        unsafe {
+            // We need to set length beforehand as wasi backend will need the length
+            ret.set_len(b)
+        };
+        read(self.value, &mut ret)?;
+        Ok(ret)
+    }
+
+    // Reads a character array as code points, including the ones that aren't Unicode scalar values
+    pub(crate) fn to_c32_vec(&self) -> Result<Vec<u32>> {
         let l = LOCK.lock();
         let b = self.get_character_array_bounds()?;
         let mut u32s = Vec::with_capacity(b);
@@ -300,10 +672,18 @@ impl BQNValue {
             // anyway
             u32s.set_len(b)
         };
-        bqn_readC32Arr(self.value, &mut u32s).unwrap();
+        bqn_readC32Arr(self.value, &mut u32s)?;
         drop(l);
 
-        Ok(u32s.into_iter().filter_map(char::from_u32).collect::<T>())
+        Ok(u32s)
+    }
+
+    fn to_char_container<T: FromIterator<char>>(&self) -> Result<T> {
+        Ok(self
+            .to_c32_vec()?
+            .into_iter()
+            .filter_map(char::from_u32)
+            .collect::<T>())
     }
 
     /// Converts `BQNValue` into vector of `char`s
@@ -323,53 +703,48 @@ impl BQNValue {
     /// # Examples
     /// ```
     /// # use cbqn::{BQN, BQNValue, eval};
-    /// # #[cfg(not(feature = "wasi-backend"))]
-    /// # {
     /// let add_three = BQNValue::fn1(|x| BQNValue::from(x.to_f64().unwrap() + 3.0));
     /// assert_eq!(BQN!(3, "{𝕏𝕨}", add_three).unwrap().to_f64().unwrap(), 6.0);
-    /// # }
     /// ```
     ///
-    /// # Panics
-    ///
-    /// * If called from a BQNValue::fn1 or BQNValue::fn2 function
-    ///
     /// # Implementation note
     ///
     /// Calling this function will allocate memory that will last for the lifetime of the program.
     /// Calling it with two identical closures, but with different lifetimes, will allocate the
-    /// memory multiple times.
+    /// memory multiple times. Use [`BQNValue::closure1`] or [`BQNValue::closure2`] for functions
+    /// that capture their environment.
+    ///
+    /// A panic in `func` is caught and thrown as a BQN error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if CBQN fails to create the function. See [`BQNValue::try_fn1`] for a constructor
+    /// that returns the error.
     ///
     /// # Backend support
     ///
-    /// Not supported in WASI backend
+    /// In WASI backend, `BQN.wasm` must export a growable function table. See the crate README.
+    /// Panics are returned from the call that ran the BQN code, but they can't be caught with `⎊`
+    /// in WASI backend.
     pub fn fn1(func: fn(&BQNValue) -> BQNValue) -> BQNValue {
-        INIT.call_once(|| {
-            let _l = LOCK.lock();
-            bqn_init().unwrap();
-        });
-
         let mut key = 0;
         FNS.with(|fns| {
             let mut boundfns = fns.borrow_mut();
-            let mut exists = false;
-            for f in boundfns.boundfn_1.iter() {
-                if *f as usize == func as usize {
-                    exists = true;
-                    break;
+            key = match boundfns
+                .boundfn_1
+                .iter()
+                .position(|f| *f as usize == func as usize)
+            {
+                Some(idx) => idx as u64,
+                None => {
+                    boundfns.boundfn_1.push(func);
+                    boundfns.boundfn_1.len() as u64 - 1
                 }
-            }
-            if !exists {
-                boundfns.boundfn_1.push(func);
-                key = boundfns.boundfn_1.len() as u64 - 1;
-            }
+            };
         });
 
         let obj = BQNValue::from(f64::from_bits(key));
-        let _l = LOCK.lock();
-        BQNValue {
-            value: bqn_makeBoundFn1(Some(boundfn_1_wrapper), obj.value).unwrap(),
-        }
+        make_boundfn_1(boundfn_1_wrapper, &obj).unwrap()
     }
 
     /// Generates a BQNValue from a Rust function
@@ -379,78 +754,309 @@ impl BQNValue {
     /// # Examples
     /// ```
     /// # use cbqn::{BQN, BQNValue, eval};
-    /// # #[cfg(not(feature = "wasi-backend"))]
-    /// # {
     /// let multiply = BQNValue::fn2(|w, x| BQNValue::from(w.to_f64().unwrap() *
     /// x.to_f64().unwrap()));
     /// assert_eq!(BQN!(multiply, "{𝕎´𝕩}", [1,2,3,4,5]).unwrap().to_f64().unwrap(), 120.0);
-    /// # }
     /// ```
     ///
-    /// # Panics
-    ///
-    /// * If called from a BQNValue::fn1 or BQNValue::fn2 function
-    ///
     /// # Implementation note
     ///
     /// Calling this function will allocate memory that will last for the lifetime of the program.
     /// Calling it with two identical closures, but with different lifetimes, will allocate the
-    /// memory multiple times.
+    /// memory multiple times. Use [`BQNValue::closure1`] or [`BQNValue::closure2`] for functions
+    /// that capture their environment.
+    ///
+    /// A panic in `func` is caught and thrown as a BQN error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if CBQN fails to create the function. See [`BQNValue::try_fn2`] for a constructor
+    /// that returns the error.
     ///
     /// # Backend support
     ///
-    /// Not supported in WASI backend
+    /// In WASI backend, `BQN.wasm` must export a growable function table. See the crate README.
+    /// Panics are returned from the call that ran the BQN code, but they can't be caught with `⎊`
+    /// in WASI backend.
     pub fn fn2(func: fn(&BQNValue, &BQNValue) -> BQNValue) -> BQNValue {
-        INIT.call_once(|| {
-            let _l = LOCK.lock();
-            bqn_init().unwrap()
-        });
-
         let mut key = 0;
         FNS.with(|fns| {
             let mut boundfns = fns.borrow_mut();
-            let mut exists = false;
-            for f in boundfns.boundfn_2.iter() {
-                if *f as usize == func as usize {
-                    exists = true;
-                    break;
+            key = match boundfns
+                .boundfn_2
+                .iter()
+                .position(|f| *f as usize == func as usize)
+            {
+                Some(idx) => idx as u64,
+                None => {
+                    boundfns.boundfn_2.push(func);
+                    boundfns.boundfn_2.len() as u64 - 1
                 }
-            }
-            if !exists {
-                boundfns.boundfn_2.push(func);
-                key = boundfns.boundfn_2.len() as u64 - 1;
-            }
+            };
         });
 
         let obj = BQNValue::from(f64::from_bits(key));
-        let _l = LOCK.lock();
-        BQNValue {
-            value: bqn_makeBoundFn2(Some(boundfn_2_wrapper), obj.value).unwrap(),
-        }
+        make_boundfn_2(boundfn_2_wrapper, &obj).unwrap()
+    }
+
+    /// Generates a BQNValue from a Rust closure
+    ///
+    /// The closure receives one argument. Unlike [`BQNValue::fn1`], the closure may capture its
+    /// environment. The captured environment is dropped after the BQN function has been freed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, eval};
+    /// let offset = 3.0;
+    /// let add_offset = BQNValue::closure1(move |x| BQNValue::from(x.to_f64().unwrap() + offset));
+    /// assert_eq!(BQN!(3, "{𝕏𝕨}", add_offset).unwrap().to_f64().unwrap(), 6.0);
+    /// ```
+    ///
+    /// # Implementation note
+    ///
+    /// CBQN does not notify the host when a bound function is freed. Closures of freed BQN
+    /// functions are collected when new closures are registered, so the captured environment may
+    /// outlive the BQN function for a while.
+    ///
+    /// Closures are registered on the thread that creates them. Calling the function on another
+    /// thread throws a BQN error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if CBQN fails to create the function. See [`BQNValue::try_fn1`] for a constructor
+    /// that returns the error.
+    ///
+    /// # Backend support
+    ///
+    /// In WASI backend, `BQN.wasm` must export a growable function table. See the crate README.
+    /// Panics are returned from the call that ran the BQN code, but they can't be caught with `⎊`
+    /// in WASI backend.
+    pub fn closure1<F>(func: F) -> BQNValue
+    where
+        F: Fn(&BQNValue) -> BQNValue + 'static,
+    {
+        BQNValue::try_fn1(move |x| Ok(func(x))).unwrap()
+    }
+
+    /// Generates a BQNValue from a Rust closure
+    ///
+    /// The closure receives two arguments. Unlike [`BQNValue::fn2`], the closure may capture its
+    /// environment. The captured environment is dropped after the BQN function has been freed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, eval};
+    /// let scale = 2.0;
+    /// let scaled_product = BQNValue::closure2(move |w, x| {
+    ///     BQNValue::from(scale * w.to_f64().unwrap() * x.to_f64().unwrap())
+    /// });
+    /// let product = scaled_product.call2(&3.into(), &4.into()).unwrap();
+    /// assert_eq!(product.to_f64().unwrap(), 24.0);
+    /// ```
+    ///
+    /// # Implementation note
+    ///
+    /// CBQN does not notify the host when a bound function is freed. Closures of freed BQN
+    /// functions are collected when new closures are registered, so the captured environment may
+    /// outlive the BQN function for a while.
+    ///
+    /// Closures are registered on the thread that creates them. Calling the function on another
+    /// thread throws a BQN error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if CBQN fails to create the function. See [`BQNValue::try_fn2`] for a constructor
+    /// that returns the error.
+    ///
+    /// # Backend support
+    ///
+    /// In WASI backend, `BQN.wasm` must export a growable function table. See the crate README.
+    /// Panics are returned from the call that ran the BQN code, but they can't be caught with `⎊`
+    /// in WASI backend.
+    pub fn closure2<F>(func: F) -> BQNValue
+    where
+        F: Fn(&BQNValue, &BQNValue) -> BQNValue + 'static,
+    {
+        BQNValue::try_fn2(move |w, x| Ok(func(w, x))).unwrap()
+    }
+
+    /// Generates a BQNValue from a mutable Rust closure
+    ///
+    /// Same as [`BQNValue::closure1`], but accepts a closure that mutates its captured state.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, eval};
+    /// let mut calls = 0;
+    /// let counter = BQNValue::closure1_mut(move |_| {
+    ///     calls += 1;
+    ///     BQNValue::from(calls)
+    /// });
+    /// assert_eq!(BQN!(counter, "{𝕎𝕩}¨", [0, 0, 0]).unwrap().to_f64_vec().unwrap(), [1.0, 2.0, 3.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// * If CBQN fails to create the function
+    /// * If the closure is called recursively from within itself
+    ///
+    /// # Backend support
+    ///
+    /// In WASI backend, `BQN.wasm` must export a growable function table. See the crate README.
+    /// Panics are returned from the call that ran the BQN code, but they can't be caught with `⎊`
+    /// in WASI backend.
+    pub fn closure1_mut<F>(func: F) -> BQNValue
+    where
+        F: FnMut(&BQNValue) -> BQNValue + 'static,
+    {
+        let func = RefCell::new(func);
+        BQNValue::closure1(move |x| (func.borrow_mut())(x))
+    }
+
+    /// Generates a BQNValue from a mutable Rust closure
+    ///
+    /// Same as [`BQNValue::closure2`], but accepts a closure that mutates its captured state.
+    ///
+    /// # Panics
+    ///
+    /// * If CBQN fails to create the function
+    /// * If the closure is called recursively from within itself
+    ///
+    /// # Backend support
+    ///
+    /// In WASI backend, `BQN.wasm` must export a growable function table. See the crate README.
+    /// Panics are returned from the call that ran the BQN code, but they can't be caught with `⎊`
+    /// in WASI backend.
+    pub fn closure2_mut<F>(func: F) -> BQNValue
+    where
+        F: FnMut(&BQNValue, &BQNValue) -> BQNValue + 'static,
+    {
+        let func = RefCell::new(func);
+        BQNValue::closure2(move |w, x| (func.borrow_mut())(w, x))
+    }
+
+    /// Generates a fallible BQNValue from a Rust closure
+    ///
+    /// The closure receives one argument. An error returned from the closure is thrown as a BQN
+    /// error, which can be caught with `⎊` in BQN code. Uncaught errors are returned as
+    /// `Error::BQN` from the `eval`, `call1` or `call2` call that ran the BQN code. Panics in the
+    /// closure are thrown as BQN errors in the same way.
+    ///
+    /// Returns an error if CBQN fails to create the function. Calling the function on another
+    /// thread than the one that created it throws a BQN error.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, Error, eval};
+    /// # #[cfg(not(feature = "wasi-backend"))]
+    /// # {
+    /// let checked_sqrt = BQNValue::try_fn1(|x| {
+    ///     let x = x.to_f64()?;
+    ///     if x < 0.0 {
+    ///         return Err(Error::InvalidType("negative number".into()));
+    ///     }
+    ///     Ok(BQNValue::from(x.sqrt()))
+    /// })
+    /// .unwrap();
+    /// assert_eq!(checked_sqrt.call1(&4.into()).unwrap().to_f64().unwrap(), 2.0);
+    /// assert!(checked_sqrt.call1(&(-4).into()).is_err());
+    /// let fallback = BQN!(checked_sqrt, "{𝕎⎊0 𝕩}", -4).unwrap();
+    /// assert_eq!(fallback.to_f64().unwrap(), 0.0);
+    /// # }
+    /// ```
+    ///
+    /// # Backend support
+    ///
+    /// In WASI backend, `BQN.wasm` must export a growable function table. See the crate README.
+    /// Errors can't be caught with `⎊` in WASI backend as CBQN is built without error catching
+    /// support for WASI.
+    pub fn try_fn1<F>(func: F) -> Result<BQNValue>
+    where
+        F: Fn(&BQNValue) -> Result<BQNValue> + 'static,
+    {
+        let (key, anchor) = BoundFns::register_closure()?;
+        let value = make_boundfn_1(closure_1_wrapper, &anchor)?;
+        FNS.with(|fns| {
+            fns.borrow_mut().closure_1.insert(
+                key,
+                BoundClosure {
+                    anchor,
+                    func: Rc::new(func),
+                },
+            )
+        });
+        Ok(value)
     }
 
-    fn bound(&self) -> usize {
-        bqn_bound(self.value).unwrap() as usize
+    /// Generates a fallible BQNValue from a Rust closure
+    ///
+    /// The closure receives two arguments. An error returned from the closure is thrown as a BQN
+    /// error, which can be caught with `⎊` in BQN code. Uncaught errors are returned as
+    /// `Error::BQN` from the `eval`, `call1` or `call2` call that ran the BQN code. Panics in the
+    /// closure are thrown as BQN errors in the same way.
+    ///
+    /// Returns an error if CBQN fails to create the function. Calling the function on another
+    /// thread than the one that created it throws a BQN error.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, Error, eval};
+    /// let checked_div = BQNValue::try_fn2(|w, x| {
+    ///     let (w, x) = (w.to_f64()?, x.to_f64()?);
+    ///     if x == 0.0 {
+    ///         return Err(Error::InvalidType("division by zero".into()));
+    ///     }
+    ///     Ok(BQNValue::from(w / x))
+    /// })
+    /// .unwrap();
+    /// assert_eq!(checked_div.call2(&6.into(), &3.into()).unwrap().to_f64().unwrap(), 2.0);
+    /// assert!(checked_div.call2(&6.into(), &0.into()).is_err());
+    /// ```
+    ///
+    /// # Backend support
+    ///
+    /// In WASI backend, `BQN.wasm` must export a growable function table. See the crate README.
+    /// Errors can't be caught with `⎊` in WASI backend as CBQN is built without error catching
+    /// support for WASI.
+    pub fn try_fn2<F>(func: F) -> Result<BQNValue>
+    where
+        F: Fn(&BQNValue, &BQNValue) -> Result<BQNValue> + 'static,
+    {
+        let (key, anchor) = BoundFns::register_closure()?;
+        let value = make_boundfn_2(closure_2_wrapper, &anchor)?;
+        FNS.with(|fns| {
+            fns.borrow_mut().closure_2.insert(
+                key,
+                BoundClosure {
+                    anchor,
+                    func: Rc::new(func),
+                },
+            )
+        });
+        Ok(value)
     }
 
-    fn direct_arr_type(&self) -> u32 {
-        bqn_directArrType(self.value).unwrap()
+    fn bound(&self) -> Result<usize> {
+        Ok(bqn_bound(self.value)? as usize)
+    }
+
+    fn direct_arr_type(&self) -> Result<u32> {
+        bqn_directArrType(self.value)
     }
 
     fn get_character_array_bounds(&self) -> Result<usize> {
-        if self.bqn_type() != BQNType::Array {
+        if self.try_bqn_type()? != BQNType::Array {
             return Err(Error::InvalidType("value isn't an array".into()));
         }
-        let b = self.bound();
-        if !self.known_char_arr() {
+        let b = self.bound()?;
+        if !self.known_char_arr()? {
             for i in 0..b {
-                let t = BQNType::try_from({
+                let t = to_bqn_type({
                     let v = bqn_pick(self.value, i)?;
                     let t = bqn_type(v)?;
                     bqn_free(v)?;
                     t
-                })
-                .expect("expected known type");
+                })?;
 
                 if t != BQNType::Character {
                     return Err(Error::InvalidType("value isn't a character array".into()));
@@ -461,19 +1067,18 @@ impl BQNValue {
     }
 
     fn get_numeric_array_bounds(&self) -> Result<usize> {
-        if self.bqn_type() != BQNType::Array {
+        if self.try_bqn_type()? != BQNType::Array {
             return Err(Error::InvalidType("value isn't an array".into()));
         }
-        let b = self.bound();
-        if !self.known_f64_arr() {
+        let b = self.bound()?;
+        if !self.known_f64_arr()? {
             for i in 0..b {
-                let t = BQNType::try_from({
+                let t = to_bqn_type({
                     let v = bqn_pick(self.value, i)?;
                     let t = bqn_type(v)?;
                     bqn_free(v)?;
                     t
-                })
-                .expect("expected known type");
+                })?;
 
                 if t != BQNType::Number {
                     return Err(Error::InvalidType("value isn't a f64 array".into()));
@@ -485,93 +1090,270 @@ impl BQNValue {
 
     // This function returns whether it's known that the array elements are of type f64
     // Returns false if *it is not known* whether the array elements are of type f64 or not
-    fn known_f64_arr(&self) -> bool {
+    fn known_f64_arr(&self) -> Result<bool> {
         #![allow(non_upper_case_globals)]
-        matches!(
-            self.direct_arr_type(),
+        Ok(matches!(
+            self.direct_arr_type()?,
             BQNElType_elt_f64 | BQNElType_elt_i32 | BQNElType_elt_i16 | BQNElType_elt_i8
-        )
+        ))
     }
 
     // This function returns whether it's known that the array elements are of type char
     // Returns false if *it is not known* whether the array elements are of type char or not
-    fn known_char_arr(&self) -> bool {
+    fn known_char_arr(&self) -> Result<bool> {
         #![allow(non_upper_case_globals)]
-        matches!(
-            self.direct_arr_type(),
+        Ok(matches!(
+            self.direct_arr_type()?,
             BQNElType_elt_c32 | BQNElType_elt_c16 | BQNElType_elt_c8
-        )
+        ))
+    }
+
+    fn element(&self, i: usize) -> Result<BQNValue> {
+        let _l = LOCK.lock();
+        Ok(BQNValue::new(bqn_pick(self.value, i)?))
     }
+
+    /// Clones `BQNValue`, returning an error if the backend fails
+    pub fn try_clone(&self) -> Result<BQNValue> {
+        let _l = LOCK.lock();
+        Ok(BQNValue::new(bqn_copy(self.value)?))
+    }
+}
+
+fn to_bqn_type(t: i32) -> Result<BQNType> {
+    BQNType::try_from(t).map_err(|e| Error::NotSupported(format!("{e}: {t}")))
 }
 
 impl fmt::Debug for BQNValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let fmt = crate::eval("•Fmt").expect("fmt");
-        let formatted = fmt.call1(self).expect("fmt.call1");
-        write!(
-            f,
-            "{}",
-            formatted.to_string().expect("formatted.to_string()")
-        )
+        let formatted = cache::FMT
+            .call1(self)
+            .and_then(|formatted| formatted.to_string());
+        match formatted {
+            Ok(formatted) => write!(f, "{formatted}"),
+            Err(e) => write!(f, "<BQNValue: {e}>"),
+        }
     }
 }
 
 impl Clone for BQNValue {
     fn clone(&self) -> BQNValue {
-        let _l = LOCK.lock();
-        BQNValue {
-            value: bqn_copy(self.value).unwrap(),
-        }
+        self.try_clone().unwrap()
     }
 }
 
 impl Drop for BQNValue {
     fn drop(&mut self) {
         let _l = LOCK.lock();
-        bqn_free(self.value).unwrap();
+        // Panicking in drop would abort the program if it's already panicking, so a value that
+        // can't be freed is leaked instead
+        let _ = bqn_free(self.value);
     }
 }
 
+type Closure1 = Rc<dyn Fn(&BQNValue) -> Result<BQNValue>>;
+type Closure2 = Rc<dyn Fn(&BQNValue, &BQNValue) -> Result<BQNValue>>;
+
+// Closures of bound functions are dropped after their BQN function has been freed. CBQN does not
+// tell when that happens, so every closure has an anchor value that is passed to CBQN as the
+// bound function object. The registry holds one reference to the anchor and the bound function
+// holds another, so once the reference count of the anchor drops to one, the closure can be
+// dropped.
+pub(crate) struct BoundClosure<F> {
+    anchor: BQNValue,
+    func: F,
+}
+
+// Minimum amount of registered closures before checking for closures that can be dropped
+const CLOSURE_COLLECT_THRESHOLD: usize = 16;
+
 #[derive(Default)]
 pub(crate) struct BoundFns {
     boundfn_1: Vec<fn(&BQNValue) -> BQNValue>,
     boundfn_2: Vec<fn(&BQNValue, &BQNValue) -> BQNValue>,
+    closure_1: HashMap<u64, BoundClosure<Closure1>>,
+    closure_2: HashMap<u64, BoundClosure<Closure2>>,
+    collect_at: usize,
+}
+
+// Closure keys are unique across threads, so that calling a closure on another thread than the one
+// it was registered on fails instead of calling an unrelated closure with the same key
+static NEXT_CLOSURE_KEY: AtomicU64 = AtomicU64::new(0);
+
+impl BoundFns {
+    // Reserves a key for a new closure and returns it with the anchor value containing the key.
+    // Drops the closures of freed BQN functions if enough closures have been registered since the
+    // last time.
+    fn register_closure() -> Result<(u64, BQNValue)> {
+        let (key, collect) = FNS.with(|fns| {
+            let boundfns = fns.borrow();
+            let key = NEXT_CLOSURE_KEY.fetch_add(1, Ordering::Relaxed);
+            let count = boundfns.closure_1.len() + boundfns.closure_2.len();
+            (
+                key,
+                count >= boundfns.collect_at.max(CLOSURE_COLLECT_THRESHOLD),
+            )
+        });
+        if collect {
+            BoundFns::collect_closures();
+        }
+
+        Ok((key, BQNValue::try_from_f64_slice(&[f64::from_bits(key)])?))
+    }
+
+    // Drops the closures whose BQN functions have been freed
+    fn collect_closures() {
+        // Calling •internal.Refc adds a reference to the anchor for the duration of the call, so
+        // an anchor only referenced by the registry has a reference count of two.
+        let is_alive = |anchor: &BQNValue| {
+            REFC.call1(anchor)
+                .and_then(|r| r.to_f64())
+                .map_or(true, |r| r > 2.0)
+        };
+
+        let (dead_1, dead_2) = FNS.with(|fns| {
+            let mut boundfns = fns.borrow_mut();
+            let (alive_1, dead_1): (HashMap<_, _>, HashMap<_, _>) =
+                mem::take(&mut boundfns.closure_1)
+                    .into_iter()
+                    .partition(|(_, c)| is_alive(&c.anchor));
+            let (alive_2, dead_2): (HashMap<_, _>, HashMap<_, _>) =
+                mem::take(&mut boundfns.closure_2)
+                    .into_iter()
+                    .partition(|(_, c)| is_alive(&c.anchor));
+            boundfns.closure_1 = alive_1;
+            boundfns.closure_2 = alive_2;
+            boundfns.collect_at = 2 * (boundfns.closure_1.len() + boundfns.closure_2.len());
+            (dead_1, dead_2)
+        });
+
+        // Captured values are dropped only after the registry has been released, as dropping them
+        // may run arbitrary code
+        drop(dead_1);
+        drop(dead_2);
+    }
 }
 
 thread_local! {
     static FNS: RefCell<BoundFns> = RefCell::new(BoundFns::default());
 }
 
+static REFC: Compiled = Compiled::new("•internal.Refc");
+
+// The Rust side of a bound function returns an `⟨error, value⟩` pair, as errors cannot be thrown
+// through Rust code. The bound function is wrapped in a BQN function that either returns the
+// value or throws it as an error.
+static BOUNDFN_1_CHECK: Compiled = Compiled::new("{R←𝕩 ⋄ {e‿v←R 𝕩 ⋄ {𝕩!0}⍟e v}}");
+static BOUNDFN_2_CHECK: Compiled = Compiled::new("{R←𝕩 ⋄ {e‿v←𝕨 R 𝕩 ⋄ {𝕩!0}⍟e v}}");
+
+// Creates a bound function that calls `wrapper` with `obj`
+fn make_boundfn_1(
+    wrapper: unsafe extern "C" fn(BQNV, BQNV) -> BQNV,
+    obj: &BQNValue,
+) -> Result<BQNValue> {
+    let boundfn = {
+        let _l = LOCK.lock();
+        BQNValue::new(bqn_makeBoundFn1(Some(wrapper), obj.value)?)
+    };
+    BOUNDFN_1_CHECK.call1(&boundfn)
+}
+
+fn make_boundfn_2(
+    wrapper: unsafe extern "C" fn(BQNV, BQNV, BQNV) -> BQNV,
+    obj: &BQNValue,
+) -> Result<BQNValue> {
+    let boundfn = {
+        let _l = LOCK.lock();
+        BQNValue::new(bqn_makeBoundFn2(Some(wrapper), obj.value)?)
+    };
+    BOUNDFN_2_CHECK.call1(&boundfn)
+}
+
+// Panics must not unwind into CBQN, so they are caught and thrown as BQN errors instead
+fn boundfn_result(ret: thread::Result<Result<BQNValue>>) -> BQNV {
+    let ret = match ret {
+        Ok(Ok(v)) => BQNValue::from([BQNValue::from(0), v]),
+        Ok(Err(e)) => {
+            // Errors from BQN code called by the bound function are rethrown as they were
+            let value = match e {
+                Error::BQN(e) => e.value().clone(),
+                e => BQNValue::from(e.to_string()),
+            };
+            BQNValue::from([BQNValue::from(1), value])
+        }
+        Err(payload) => {
+            let msg = if let Some(msg) = payload.downcast_ref::<&str>() {
+                msg
+            } else if let Some(msg) = payload.downcast_ref::<String>() {
+                msg.as_str()
+            } else {
+                "Box<dyn Any>"
+            };
+            BQNValue::from([
+                BQNValue::from(1),
+                BQNValue::from(format!("Rust panic: {msg}")),
+            ])
+        }
+    };
+    let retval = ret.value;
+    mem::forget(ret);
+    retval
+}
+
 unsafe extern "C" fn boundfn_1_wrapper(obj: BQNV, x: BQNV) -> BQNV {
-    let key = BQNValue::new(obj)
-        .to_f64()
-        .expect("boundfn obj to_f64")
-        .to_bits() as usize;
-
-    FNS.with(|fns| {
-        let boundfns = fns.borrow();
-        let tgt = &boundfns.boundfn_1[key];
-        let ret = tgt(&BQNValue::new(x));
-        let retval = ret.value;
-        mem::forget(ret);
-        retval
-    })
+    let (obj, x) = (BQNValue::new(obj), BQNValue::new(x));
+    boundfn_result(panic::catch_unwind(AssertUnwindSafe(|| {
+        let key = obj.to_f64().expect("boundfn obj to_f64").to_bits() as usize;
+        let tgt = FNS.with(|fns| fns.borrow().boundfn_1[key]);
+        Ok(tgt(&x))
+    })))
+}
+
+fn closure_key(obj: &BQNValue) -> u64 {
+    obj.to_f64_vec().expect("boundfn obj to_f64_vec")[0].to_bits()
+}
+
+fn closure_not_found() -> Error {
+    Error::NotSupported("closure was created on another thread".into())
+}
+
+unsafe extern "C" fn closure_1_wrapper(obj: BQNV, x: BQNV) -> BQNV {
+    let (obj, x) = (BQNValue::new(obj), BQNValue::new(x));
+    boundfn_result(panic::catch_unwind(AssertUnwindSafe(|| {
+        let key = closure_key(&obj);
+        let tgt = FNS.with(|fns| {
+            fns.borrow()
+                .closure_1
+                .get(&key)
+                .map(|c| c.func.clone())
+                .ok_or_else(closure_not_found)
+        })?;
+        tgt(&x)
+    })))
 }
 
 unsafe extern "C" fn boundfn_2_wrapper(obj: BQNV, w: BQNV, x: BQNV) -> BQNV {
-    let key = BQNValue::new(obj)
-        .to_f64()
-        .expect("boundfn obj to_f64")
-        .to_bits() as usize;
-
-    FNS.with(|fns| {
-        let boundfns = fns.borrow();
-        let tgt = &boundfns.boundfn_2[key];
-        let ret = tgt(&BQNValue::new(w), &BQNValue::new(x));
-        let retval = ret.value;
-        mem::forget(ret);
-        retval
-    })
+    let (obj, w, x) = (BQNValue::new(obj), BQNValue::new(w), BQNValue::new(x));
+    boundfn_result(panic::catch_unwind(AssertUnwindSafe(|| {
+        let key = obj.to_f64().expect("boundfn obj to_f64").to_bits() as usize;
+        let tgt = FNS.with(|fns| fns.borrow().boundfn_2[key]);
+        Ok(tgt(&w, &x))
+    })))
+}
+
+unsafe extern "C" fn closure_2_wrapper(obj: BQNV, w: BQNV, x: BQNV) -> BQNV {
+    let (obj, w, x) = (BQNValue::new(obj), BQNValue::new(w), BQNValue::new(x));
+    boundfn_result(panic::catch_unwind(AssertUnwindSafe(|| {
+        let key = closure_key(&obj);
+        let tgt = FNS.with(|fns| {
+            fns.borrow()
+                .closure_2
+                .get(&key)
+                .map(|c| c.func.clone())
+                .ok_or_else(closure_not_found)
+        })?;
+        tgt(&w, &x)
+    })))
 }
 
 /// Evaluates BQN code
@@ -583,10 +1365,7 @@ unsafe extern "C" fn boundfn_2_wrapper(obj: BQNV, w: BQNV, x: BQNV) -> BQNV {
 /// let bqnfn = eval("{𝕩×10}").unwrap();
 /// ```
 pub fn eval(bqn: &str) -> Result<BQNValue> {
-    INIT.call_once(|| {
-        let _l = LOCK.lock();
-        bqn_init().unwrap();
-    });
+    try_init()?;
     let _l = LOCK.lock();
     backend_eval(bqn)
 }
@@ -598,7 +1377,25 @@ pub fn eval(bqn: &str) -> Result<BQNValue> {
 ///
 /// Mostly useful with WASI backend as it will take some hundred milliseconds to compile the WASI
 /// module, which is done at the first use of the module.
+///
+/// # Panics
+///
+/// Panics if CBQN fails to initialize. See [`try_init`].
 pub fn init() {
+    try_init().unwrap();
+}
+
+/// Initializes the CBQN interpreter, returning an error if it fails
+///
+/// Initialization is tried again on the next use of the crate after a failure.
+pub fn try_init() -> Result<()> {
+    if INITIALIZED.load(Ordering::Acquire) {
+        return Ok(());
+    }
     let _l = LOCK.lock();
-    crate::INIT.call_once(|| bqn_init().unwrap());
+    if !INITIALIZED.load(Ordering::Acquire) {
+        bqn_init()?;
+        INITIALIZED.store(true, Ordering::Release);
+    }
+    Ok(())
 }
@@ -2,12 +2,7 @@ macro_rules! impl_from_string_like {
     ($ty:ty) => {
         impl From<$ty> for BQNValue {
             fn from(v: $ty) -> BQNValue {
-                crate::INIT.call_once(|| {
-                    let _l = LOCK.lock();
-                    bqn_init().unwrap();
-                });
-                let _l = LOCK.lock();
-                BQNValue::new(bqn_makeUTF8Str(&v).unwrap())
+                BQNValue::try_from_str(&v).unwrap()
             }
         }
     };
@@ -17,16 +12,7 @@ macro_rules! impl_from_string_like_vec {
     ($ty:ty) => {
         impl From<Vec<$ty>> for BQNValue {
             fn from(arr: Vec<$ty>) -> BQNValue {
-                crate::INIT.call_once(|| {
-                    let _l = LOCK.lock();
-                    bqn_init().unwrap();
-                });
-                let mut strs = Vec::with_capacity(arr.len());
-                let _l = LOCK.lock();
-                for s in &arr {
-                    strs.push(bqn_makeUTF8Str(&s).unwrap());
-                }
-                BQNValue::new(bqn_makeObjVec(&strs).unwrap())
+                BQNValue::try_from_strs(&arr).unwrap()
             }
         }
     };
@@ -36,13 +22,7 @@ macro_rules! impl_from_slice {
     ($ty:ty, $fn:ident) => {
         impl From<$ty> for BQNValue {
             fn from(arr: $ty) -> BQNValue {
-                crate::INIT.call_once(|| {
-                    let _l = LOCK.lock();
-                    bqn_init().unwrap();
-                });
-
-                let _l = LOCK.lock();
-                BQNValue::new($fn(&arr).unwrap())
+                BQNValue::$fn(arr).unwrap()
             }
         }
     };
@@ -52,13 +32,7 @@ macro_rules! impl_from_array {
     ($ty:ty, $fn:ident) => {
         impl<const N: usize> From<[$ty; N]> for BQNValue {
             fn from(arr: [$ty; N]) -> BQNValue {
-                crate::INIT.call_once(|| {
-                    let _l = LOCK.lock();
-                    bqn_init().unwrap();
-                });
-
-                let _l = LOCK.lock();
-                BQNValue::new($fn(&arr).unwrap())
+                BQNValue::$fn(&arr).unwrap()
             }
         }
     };
@@ -71,14 +45,7 @@ macro_rules! impl_from_iterator {
             where
                 T: IntoIterator<Item = $ty>,
             {
-                crate::INIT.call_once(|| {
-                    let _l = LOCK.lock();
-                    bqn_init().unwrap();
-                });
-
-                let elems = iter.into_iter().collect::<Vec<_>>();
-                let _l = LOCK.lock();
-                BQNValue::new($fn(&elems).unwrap())
+                BQNValue::$fn(&iter.into_iter().collect::<Vec<_>>()).unwrap()
             }
         }
     };
@@ -88,13 +55,7 @@ macro_rules! impl_from_vec {
     ($ty:ty, $fn:ident) => {
         impl From<Vec<$ty>> for BQNValue {
             fn from(arr: Vec<$ty>) -> BQNValue {
-                crate::INIT.call_once(|| {
-                    let _l = LOCK.lock();
-                    bqn_init().unwrap();
-                });
-
-                let _l = LOCK.lock();
-                BQNValue::new($fn(&arr).unwrap())
+                BQNValue::$fn(&arr).unwrap()
             }
         }
     };
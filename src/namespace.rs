@@ -0,0 +1,68 @@
+use crate::cache::{eval_with_cache, Cache};
+use crate::{eval, BQNType, BQNValue, Compiled, Error, Result};
+use std::sync::OnceLock;
+
+static NS_KEYS: Compiled = Compiled::new("•ns.Keys");
+static NS_GET: Compiled = Compiled::new("•ns.Get");
+// Namespace constructors keyed by their code, kept apart from the `eval_cached` cache so that
+// creating namespaces doesn't evict values cached by users
+static NS_CONSTRUCTORS: Cache = OnceLock::new();
+
+// Normalizes `name` the same way BQN normalizes namespace field names
+pub fn normalize_name(name: &str) -> Result<String> {
+    let normalized = name
+        .chars()
+        .filter(|&c| c != '_')
+        .map(|c| c.to_ascii_lowercase())
+        .collect::<String>();
+    let mut chars = normalized.chars();
+    if chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    {
+        Ok(normalized)
+    } else {
+        Err(Error::InvalidType(format!(
+            "`{name}` isn't a valid namespace field name"
+        )))
+    }
+}
+
+// Creates a namespace from field names and values
+pub fn make_namespace(fields: Vec<(String, BQNValue)>) -> Result<BQNValue> {
+    let (names, values): (Vec<_>, Vec<_>) = fields
+        .into_iter()
+        .map(|(name, value)| normalize_name(&name).map(|n| (n, value)))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+
+    let mut sorted = names.clone();
+    sorted.sort();
+    if let Some(w) = sorted.windows(2).find(|w| w[0] == w[1]) {
+        return Err(Error::InvalidType(format!(
+            "duplicate namespace field `{}`",
+            w[0]
+        )));
+    }
+
+    if names.is_empty() {
+        return eval("{⇐}");
+    }
+
+    let constructor = format!("{{⟨{}⟩⇐𝕩}}", names.join(","));
+    eval_with_cache(&NS_CONSTRUCTORS, &constructor)?.call1(&BQNValue::try_from_values(values)?)
+}
+
+// Returns the fields of a namespace as name-value pairs
+pub fn namespace_fields(ns: &BQNValue) -> Result<Vec<(String, BQNValue)>> {
+    if ns.try_bqn_type()? != BQNType::Namespace {
+        return Err(Error::InvalidType("value isn't a namespace".into()));
+    }
+    let get = NS_GET.get()?;
+    NS_KEYS
+        .call1(ns)?
+        .to_bqnvalue_vec()?
+        .into_iter()
+        .map(|key| Ok((key.to_string()?, get.call2(ns, &key)?)))
+        .collect()
+}
@@ -0,0 +1,73 @@
+//! Conversions between `BQNValue` and [`ndarray::ArrayD`]
+//!
+//! Enabled with the `ndarray` feature. The shape of the array is preserved in both directions.
+
+use crate::{backend::*, try_init, BQNValue, Error, Result, LOCK};
+use ::ndarray::{ArrayD, IxDyn};
+
+fn make_arr<T: Clone, U>(
+    arr: ArrayD<T>,
+    make: fn(&[usize], &[U]) -> Result<BQNV>,
+    conv: fn(T) -> U,
+) -> Result<BQNValue> {
+    try_init()?;
+
+    let shape = arr.shape().to_vec();
+    // Elements are collected in logical order, so arrays in any memory layout work
+    let elems = arr.into_iter().map(conv).collect::<Vec<_>>();
+    let _l = LOCK.lock();
+    Ok(BQNValue::new(make(&shape, &elems)?))
+}
+
+fn to_arr<T>(v: &BQNValue, elems: Vec<T>) -> Result<ArrayD<T>> {
+    ArrayD::from_shape_vec(IxDyn(&v.try_shape()?), elems)
+        .map_err(|e| Error::InvalidType(format!("cannot convert value into an array: {e}")))
+}
+
+macro_rules! impl_ndarray {
+    ($ty:ty, $make:ident, $read:ident) => {
+        impl From<ArrayD<$ty>> for BQNValue {
+            fn from(arr: ArrayD<$ty>) -> BQNValue {
+                make_arr(arr, $make, |v| v).unwrap()
+            }
+        }
+
+        impl TryFrom<&BQNValue> for ArrayD<$ty> {
+            type Error = Error;
+
+            fn try_from(v: &BQNValue) -> Result<ArrayD<$ty>> {
+                to_arr(v, v.$read()?)
+            }
+        }
+    };
+}
+
+impl_ndarray!(f64, bqn_makeF64Arr, to_f64_vec);
+impl_ndarray!(i32, bqn_makeI32Arr, to_i32_vec);
+impl_ndarray!(i16, bqn_makeI16Arr, to_i16_vec);
+impl_ndarray!(i8, bqn_makeI8Arr, to_i8_vec);
+
+impl From<ArrayD<char>> for BQNValue {
+    fn from(arr: ArrayD<char>) -> BQNValue {
+        make_arr(arr, bqn_makeC32Arr, u32::from).unwrap()
+    }
+}
+
+impl TryFrom<&BQNValue> for ArrayD<char> {
+    type Error = Error;
+
+    fn try_from(v: &BQNValue) -> Result<ArrayD<char>> {
+        // `to_char_vec` skips characters that aren't Unicode scalar values, which would break the
+        // shape of the array
+        let chars = v
+            .to_c32_vec()?
+            .into_iter()
+            .map(|c| {
+                char::from_u32(c).ok_or_else(|| {
+                    Error::InvalidType(format!("{c:#x} isn't an Unicode scalar value"))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        to_arr(v, chars)
+    }
+}
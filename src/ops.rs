@@ -0,0 +1,44 @@
+use crate::{BQNValue, Compiled, Result};
+use std::ops::{Add, BitAnd, BitOr, Div, Mul, Neg, Not, Sub};
+
+// The operators call BQN primitives, so they follow BQN semantics: arithmetic is pervasive and
+// arrays of matching shapes or with a matching prefix of their shapes are combined element-wise.
+// Errors from BQN, e.g. from mismatching shapes or adding two characters, are returned in the
+// `Result`.
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $method:ident, $glyph:literal) => {
+        #[doc = concat!("Calls BQN primitive `", $glyph, "` dyadically")]
+        impl $trait<&BQNValue> for &BQNValue {
+            type Output = Result<BQNValue>;
+
+            fn $method(self, rhs: &BQNValue) -> Result<BQNValue> {
+                static PRIMITIVE: Compiled = Compiled::new($glyph);
+                PRIMITIVE.call2(self, rhs)
+            }
+        }
+    };
+}
+
+macro_rules! impl_unary_op {
+    ($trait:ident, $method:ident, $glyph:literal) => {
+        #[doc = concat!("Calls BQN primitive `", $glyph, "` monadically")]
+        impl $trait for &BQNValue {
+            type Output = Result<BQNValue>;
+
+            fn $method(self) -> Result<BQNValue> {
+                static PRIMITIVE: Compiled = Compiled::new($glyph);
+                PRIMITIVE.call1(self)
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, "+");
+impl_binary_op!(Sub, sub, "-");
+impl_binary_op!(Mul, mul, "×");
+impl_binary_op!(Div, div, "÷");
+impl_binary_op!(BitAnd, bitand, "∧");
+impl_binary_op!(BitOr, bitor, "∨");
+impl_unary_op!(Neg, neg, "-");
+impl_unary_op!(Not, not, "¬");
@@ -0,0 +1,91 @@
+use crate::{BQNType, BQNValue, Error, Result, LOCK};
+
+#[derive(Debug, PartialEq)]
+enum Segment<'a> {
+    Field(&'a str),
+    Index(usize),
+}
+
+impl BQNValue {
+    /// Returns the value at `path` inside nested namespaces and arrays
+    ///
+    /// The path consists of field names separated by `.` and indices in brackets, such as
+    /// `server.ports[2]` or `[0].name`. Field names are normalized like in
+    /// [`get_field`](BQNValue::get_field). An index picks an element from the array in ravel
+    /// order, like `i⊑⥊𝕩`, without reading the rest of the array.
+    ///
+    /// Returns `None` if a field doesn't exist or an index is out of bounds. Returns an error if
+    /// the path is malformed, or if a field is looked up from a value that isn't a namespace or an
+    /// index from a value that isn't an array.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cbqn::{BQN, BQNValue, eval, Error};
+    /// let config = BQN!("{server⇐{host⇐\"localhost\" ⋄ ports⇐80‿443‿8080}}")?;
+    /// assert_eq!(config.at("server.ports[2]")?.unwrap().to_f64()?, 8080.0);
+    /// assert_eq!(config.at("server.host")?.unwrap().to_string()?, "localhost");
+    /// assert!(config.at("server.ports[3]")?.is_none());
+    /// assert!(config.at("client.host")?.is_none());
+    /// assert!(config.at("server.host.name").is_err());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn at(&self, path: &str) -> Result<Option<BQNValue>> {
+        let segments = parse_path(path)?;
+        let _l = LOCK.lock();
+        let mut value = self.try_clone()?;
+        for segment in segments {
+            let next = match segment {
+                Segment::Field(name) => value.get_field(name)?,
+                Segment::Index(i) => {
+                    if value.try_bqn_type()? != BQNType::Array {
+                        return Err(Error::InvalidType(format!(
+                            "cannot index [{i}] into a value that isn't an array in `{path}`"
+                        )));
+                    }
+                    if i < value.bound()? {
+                        Some(value.element(i)?)
+                    } else {
+                        None
+                    }
+                }
+            };
+            match next {
+                Some(next) => value = next,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(value))
+    }
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment<'_>>> {
+    let invalid = |reason: &str| Error::InvalidType(format!("invalid path `{path}`: {reason}"));
+    let mut segments = vec![];
+    let mut rest = path;
+    loop {
+        if let Some(r) = rest.strip_prefix('[') {
+            let (index, r) = r.split_once(']').ok_or_else(|| invalid("missing `]`"))?;
+            let index = index
+                .trim()
+                .parse()
+                .map_err(|_| invalid("index isn't a non-negative integer"))?;
+            segments.push(Segment::Index(index));
+            rest = r;
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(invalid("missing field name"));
+            }
+            segments.push(Segment::Field(&rest[..end]));
+            rest = &rest[end..];
+        }
+        if rest.is_empty() {
+            return Ok(segments);
+        }
+        if let Some(r) = rest.strip_prefix('.') {
+            rest = r;
+        } else if !rest.starts_with('[') {
+            return Err(invalid("expected `.` or `[` after `]`"));
+        }
+    }
+}
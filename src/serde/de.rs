@@ -0,0 +1,443 @@
+use crate::namespace::{namespace_fields, normalize_name};
+use crate::{BQNType, BQNValue, Compiled, Error, Result};
+use ::serde::de::{
+    self, value::StringDeserializer, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor,
+};
+use std::vec;
+
+static MAJOR_CELLS: Compiled = Compiled::new("{(2⌊=𝕩)◶⟨⥊,⊢,<˘⟩𝕩}");
+
+/// Converts a `BQNValue` into a Rust value
+///
+/// Uses the same mapping between Rust and BQN values as [`to_value`](crate::to_value). In
+/// addition, structs can be deserialized from lists, in which case the elements are assigned to
+/// the fields in declaration order. Arrays with rank higher than one are deserialized as lists of
+/// their major cells.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// # use cbqn::{BQN, BQNValue, eval};
+/// #[derive(serde::Deserialize, Debug, PartialEq)]
+/// struct Config {
+///     name: String,
+///     ports: Vec<u16>,
+///     timeout: Option<f64>,
+/// }
+///
+/// let ns = BQN!("{name⇐\"server\" ⋄ ports⇐80‿443 ⋄ timeout⇐@}").unwrap();
+/// let config: Config = cbqn::from_value(&ns).unwrap();
+/// assert_eq!(
+///     config,
+///     Config {
+///         name: "server".into(),
+///         ports: vec![80, 443],
+///         timeout: None,
+///     }
+/// );
+/// # }
+/// ```
+pub fn from_value<T: DeserializeOwned>(value: &BQNValue) -> Result<T> {
+    T::deserialize(Deserializer {
+        value: value.try_clone()?,
+    })
+}
+
+struct Deserializer {
+    value: BQNValue,
+}
+
+impl Deserializer {
+    fn invalid_type(&self, expected: &str) -> Error {
+        match self.value.try_bqn_type() {
+            Ok(t) => Error::InvalidType(format!("expected {expected}, found {t:?}")),
+            Err(e) => e,
+        }
+    }
+
+    fn is_null(&self) -> Result<bool> {
+        Ok(self.value.try_bqn_type()? == BQNType::Character && self.value.to_u32()? == 0)
+    }
+
+    // Reads an integer in the range of i64 or u64
+    fn integer(&self) -> Result<i128> {
+        let f = self.value.to_f64()?;
+        // u64::MAX as f64 rounds up to 2^64, which is out of range
+        if f.fract() == 0.0 && f >= i64::MIN as f64 && f < u64::MAX as f64 {
+            Ok(f as i128)
+        } else {
+            Err(Error::InvalidType(format!(
+                "expected an integer, found {f}"
+            )))
+        }
+    }
+
+    fn int<T: TryFrom<i128>>(&self) -> Result<T> {
+        let i = self.integer()?;
+        T::try_from(i).map_err(|_| Error::InvalidType(format!("integer {i} out of range")))
+    }
+
+    // Returns the major cells of an array, or the only element of a unit array
+    fn elements(&self) -> Result<Vec<BQNValue>> {
+        if self.value.try_bqn_type()? != BQNType::Array {
+            return Err(self.invalid_type("a list"));
+        }
+        MAJOR_CELLS.call1(&self.value)?.to_bqnvalue_vec()
+    }
+
+    fn string(&self) -> Result<String> {
+        if self.value.try_bqn_type()? != BQNType::Array {
+            return Err(self.invalid_type("a string"));
+        }
+        self.value
+            .to_c32_vec()?
+            .into_iter()
+            .map(|c| {
+                char::from_u32(c).ok_or_else(|| {
+                    Error::InvalidType(format!("{c:#x} isn't a Unicode scalar value"))
+                })
+            })
+            .collect()
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value.try_bqn_type()? {
+            BQNType::Number => match self.integer() {
+                Ok(i) => match i64::try_from(i) {
+                    Ok(i) => visitor.visit_i64(i),
+                    Err(_) => visitor.visit_u64(i as u64),
+                },
+                Err(_) => visitor.visit_f64(self.value.to_f64()?),
+            },
+            BQNType::Character if self.is_null()? => visitor.visit_unit(),
+            BQNType::Character => self.deserialize_char(visitor),
+            BQNType::Array => match self.string() {
+                Ok(s) if !s.is_empty() => visitor.visit_string(s),
+                _ => self.deserialize_seq(visitor),
+            },
+            BQNType::Namespace => self.deserialize_map(visitor),
+            t => Err(Error::InvalidType(format!("cannot deserialize {t:?}"))),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.int::<u8>() {
+            Ok(0) => visitor.visit_bool(false),
+            Ok(1) => visitor.visit_bool(true),
+            _ => Err(self.invalid_type("0 or 1")),
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i8(self.int()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i16(self.int()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i32(self.int()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i64(self.int()?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u8(self.int()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u16(self.int()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u32(self.int()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u64(self.int()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_f32(self.value.to_f64()? as f32)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_f64(self.value.to_f64()?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.value.to_char()? {
+            Some(c) => visitor.visit_char(c),
+            None => Err(Error::InvalidType(
+                "character isn't a Unicode scalar value".into(),
+            )),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_string(self.string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_byte_buf(self.value.to_u8_vec()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.is_null()? {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.is_null()? {
+            visitor.visit_unit()
+        } else {
+            Err(self.invalid_type("@"))
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(ListAccess {
+            elems: self.elements()?.into_iter(),
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.value.try_bqn_type()? != BQNType::Namespace {
+            return Err(self.invalid_type("a namespace"));
+        }
+        visitor.visit_map(NamespaceAccess {
+            fields: namespace_fields(&self.value)?.into_iter(),
+            value: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.value.try_bqn_type()? {
+            BQNType::Array => self.deserialize_seq(visitor),
+            BQNType::Namespace => {
+                // Namespace field names are normalized, so they are mapped back to the names of
+                // the struct fields
+                let ns_fields = namespace_fields(&self.value)?
+                    .into_iter()
+                    .filter_map(|(name, value)| {
+                        fields
+                            .iter()
+                            .find(|f| normalize_name(f).is_ok_and(|f| f == name))
+                            .map(|f| (f.to_string(), value))
+                    })
+                    .collect::<Vec<_>>();
+                visitor.visit_map(NamespaceAccess {
+                    fields: ns_fields.into_iter(),
+                    value: None,
+                })
+            }
+            _ => Err(self.invalid_type("a namespace or a list")),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let find_variant = |name: &str| {
+            let normalized = normalize_name(name)?;
+            variants
+                .iter()
+                .find(|v| normalize_name(v).is_ok_and(|v| v == normalized))
+                .map(|v| v.to_string())
+                .ok_or_else(|| Error::InvalidType(format!("unknown variant `{name}`")))
+        };
+
+        match self.value.try_bqn_type()? {
+            BQNType::Array => visitor.visit_enum(EnumAccess {
+                variant: find_variant(&self.string()?)?,
+                value: None,
+            }),
+            BQNType::Namespace => {
+                let mut fields = namespace_fields(&self.value)?;
+                if fields.len() != 1 {
+                    return Err(Error::InvalidType(
+                        "expected a namespace with exactly one field".into(),
+                    ));
+                }
+                let (name, value) = fields.remove(0);
+                visitor.visit_enum(EnumAccess {
+                    variant: find_variant(&name)?,
+                    value: Some(value),
+                })
+            }
+            _ => Err(self.invalid_type("a string or a namespace")),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+struct ListAccess {
+    elems: vec::IntoIter<BQNValue>,
+}
+
+impl<'de> de::SeqAccess<'de> for ListAccess {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self.elems
+            .next()
+            .map(|value| seed.deserialize(Deserializer { value }))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elems.len())
+    }
+}
+
+struct NamespaceAccess {
+    fields: vec::IntoIter<(String, BQNValue)>,
+    value: Option<BQNValue>,
+}
+
+impl<'de> de::MapAccess<'de> for NamespaceAccess {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.fields.next() {
+            Some((name, value)) => {
+                self.value = Some(value);
+                let name: StringDeserializer<Error> = name.into_deserializer();
+                seed.deserialize(name).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error::InvalidType("value requested before its key".into()))?;
+        seed.deserialize(Deserializer { value })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+}
+
+struct EnumAccess {
+    variant: String,
+    value: Option<BQNValue>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+    type Error = Error;
+    type Variant = VariantAccess;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, VariantAccess)> {
+        let variant: StringDeserializer<Error> = self.variant.into_deserializer();
+        Ok((
+            seed.deserialize(variant)?,
+            VariantAccess { value: self.value },
+        ))
+    }
+}
+
+struct VariantAccess {
+    value: Option<BQNValue>,
+}
+
+impl VariantAccess {
+    fn deserializer(self) -> Result<Deserializer> {
+        self.value
+            .map(|value| Deserializer { value })
+            .ok_or_else(|| Error::InvalidType("expected an enum variant with data".into()))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantAccess {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.value {
+            None => Ok(()),
+            Some(_) => Err(Error::InvalidType("expected a unit variant".into())),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self.deserializer()?)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(self.deserializer()?, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_struct(self.deserializer()?, "", fields, visitor)
+    }
+}
@@ -0,0 +1,21 @@
+//! Serde support for `BQNValue`, enabled with the `serde` feature
+
+mod de;
+mod ser;
+
+pub use de::from_value;
+pub use ser::to_value;
+
+use crate::Error;
+
+impl ::serde::ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::InvalidType(msg.to_string())
+    }
+}
+
+impl ::serde::de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::InvalidType(msg.to_string())
+    }
+}
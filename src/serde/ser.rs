@@ -0,0 +1,347 @@
+use crate::namespace::make_namespace;
+use crate::traits::exact_f64;
+use crate::{BQNValue, Error, Result};
+use ::serde::ser::{self, Serialize};
+
+/// Converts a Rust value into a `BQNValue`
+///
+/// Rust values are mapped to BQN values as follows:
+///
+/// | Rust                               | BQN                                          |
+/// |------------------------------------|----------------------------------------------|
+/// | `bool`                             | `0` or `1`                                   |
+/// | integers and floats                | number                                       |
+/// | `char`                             | character                                    |
+/// | `String`, `&str`                   | string                                       |
+/// | byte arrays, sequences, tuples     | list                                         |
+/// | structs, maps with string keys     | namespace                                    |
+/// | `None`, `()` and unit structs      | null character `@`                           |
+/// | `Some(v)`, newtype structs         | same as `v`                                  |
+/// | unit variants                      | name of the variant as a string              |
+/// | other enum variants                | namespace with the variant as its only field |
+///
+/// Because `None` is represented as `@`, `Some('\0')` is deserialized back as `None`.
+///
+/// BQN numbers are `f64`s, so `i64` and `u64` values that `f64` can't represent exactly, such as
+/// odd numbers above 2^53, return an error instead of being rounded.
+///
+/// BQN normalizes namespace field names by removing underscores and converting them to lowercase,
+/// so a struct field `user_id` is stored as `userid` in BQN. Field names must be valid BQN names
+/// after the normalization.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// # use cbqn::{BQN, BQNValue, eval};
+/// #[derive(serde::Serialize)]
+/// struct Point {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// let p = cbqn::to_value(&Point { x: 1.0, y: 2.0 }).unwrap();
+/// assert_eq!(BQN!("{𝕩.x+𝕩.y}", p).unwrap().to_f64().unwrap(), 3.0);
+/// # }
+/// ```
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<BQNValue> {
+    value.serialize(Serializer)
+}
+
+struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = BQNValue;
+    type Error = Error;
+
+    type SerializeSeq = SerializeList;
+    type SerializeTuple = SerializeList;
+    type SerializeTupleStruct = SerializeList;
+    type SerializeTupleVariant = SerializeVariant<SerializeList>;
+    type SerializeMap = SerializeNamespace;
+    type SerializeStruct = SerializeNamespace;
+    type SerializeStructVariant = SerializeVariant<SerializeNamespace>;
+
+    fn serialize_bool(self, v: bool) -> Result<BQNValue> {
+        BQNValue::try_from_f64(if v { 1.0 } else { 0.0 })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<BQNValue> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<BQNValue> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<BQNValue> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<BQNValue> {
+        self.serialize_f64(exact_f64(v.into())?)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<BQNValue> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<BQNValue> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<BQNValue> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<BQNValue> {
+        self.serialize_f64(exact_f64(v.into())?)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<BQNValue> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<BQNValue> {
+        BQNValue::try_from_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<BQNValue> {
+        BQNValue::try_from_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<BQNValue> {
+        BQNValue::try_from_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<BQNValue> {
+        BQNValue::try_from_i16_slice(&v.iter().map(|&b| i16::from(b)).collect::<Vec<_>>())
+    }
+
+    fn serialize_none(self) -> Result<BQNValue> {
+        BQNValue::try_null()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<BQNValue> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<BQNValue> {
+        BQNValue::try_null()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<BQNValue> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<BQNValue> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<BQNValue> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<BQNValue> {
+        make_namespace(vec![(variant.to_owned(), value.serialize(self)?)])
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeList> {
+        Ok(SerializeList {
+            elems: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeList> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeList> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeList>> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeNamespace> {
+        Ok(SerializeNamespace {
+            fields: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeNamespace> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeNamespace>> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+struct SerializeList {
+    elems: Vec<BQNValue>,
+}
+
+impl ser::SerializeSeq for SerializeList {
+    type Ok = BQNValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.elems.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<BQNValue> {
+        BQNValue::try_from_values(self.elems)
+    }
+}
+
+impl ser::SerializeTuple for SerializeList {
+    type Ok = BQNValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<BQNValue> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeList {
+    type Ok = BQNValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<BQNValue> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+struct SerializeNamespace {
+    fields: Vec<(String, BQNValue)>,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeNamespace {
+    type Ok = BQNValue;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        let key = key
+            .serialize(Serializer)?
+            .to_string()
+            .map_err(|_| Error::InvalidType("namespace field names must be strings".into()))?;
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::InvalidType("value serialized before its key".into()))?;
+        self.fields.push((key, value.serialize(Serializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<BQNValue> {
+        make_namespace(self.fields)
+    }
+}
+
+impl ser::SerializeStruct for SerializeNamespace {
+    type Ok = BQNValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.fields
+            .push((key.to_owned(), value.serialize(Serializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<BQNValue> {
+        make_namespace(self.fields)
+    }
+}
+
+// Enum variants with data are wrapped in a namespace that has the variant name as its only field
+struct SerializeVariant<T> {
+    variant: &'static str,
+    inner: T,
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeList> {
+    type Ok = BQNValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<BQNValue> {
+        let value = ser::SerializeSeq::end(self.inner)?;
+        make_namespace(vec![(self.variant.to_owned(), value)])
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeNamespace> {
+    type Ok = BQNValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<BQNValue> {
+        let value = ser::SerializeStruct::end(self.inner)?;
+        make_namespace(vec![(self.variant.to_owned(), value)])
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{BQNValue, Evaluator, EvaluatorBuilder, Result};
+
+/// How a [`Session`] treats definitions made in earlier evaluations
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplMode {
+    /// Variables can be redefined with `←` in later evaluations
+    #[default]
+    Loose,
+    /// Redefining a variable with `←` is an error, as in a single BQN program. Existing variables
+    /// can still be modified with `↩`.
+    Strict,
+}
+
+impl ReplMode {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ReplMode::Loose => "loose",
+            ReplMode::Strict => "strict",
+        }
+    }
+}
+
+/// A REPL-style evaluation session
+///
+/// Unlike [`eval`](crate::eval), which evaluates every piece of code in a scope of its own, code
+/// evaluated in a `Session` shares its scope with the earlier evaluations of the same session.
+/// Sessions are independent of each other.
+///
+/// A session is an [`Evaluator`] built with [`EvaluatorBuilder::repl`]. Sessions with custom
+/// primitives or system values can be created with [`EvaluatorBuilder`]. The code evaluated in a
+/// session can't export variables with `⇐` at the top level.
+///
+/// # Examples
+/// ```
+/// # use cbqn::{Error, Session};
+/// let session = Session::new()?;
+/// session.eval("a←1")?;
+/// assert_eq!(session.eval("a+1")?.to_f64()?, 2.0);
+///
+/// let other = Session::new()?;
+/// assert!(other.eval("a").is_err());
+/// # Ok::<(), Error>(())
+/// ```
+pub struct Session {
+    evaluator: Evaluator,
+}
+
+impl Session {
+    /// Creates a new session with [`ReplMode::Loose`]
+    pub fn new() -> Result<Session> {
+        Session::with_mode(ReplMode::default())
+    }
+
+    /// Creates a new session that uses `mode`
+    pub fn with_mode(mode: ReplMode) -> Result<Session> {
+        let evaluator = EvaluatorBuilder::new().repl(mode).build()?;
+        Ok(Session { evaluator })
+    }
+
+    /// Evaluates BQN code in the scope of this session
+    pub fn eval(&self, bqn: &str) -> Result<BQNValue> {
+        self.evaluator.eval(bqn)
+    }
+}
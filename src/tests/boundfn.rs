@@ -60,9 +60,7 @@ fn clone() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Ignored until I figure out how to make this work with Rust's changed extern C unwinding"]
-#[should_panic]
-fn boundfn_inside_boundfn() {
+fn boundfn_inside_boundfn() -> Result<()> {
     let to_upper = BQNValue::fn1(|x| {
         let to_lower = BQNValue::fn1(|x| {
             let s = x.to_string().unwrap();
@@ -72,7 +70,101 @@ fn boundfn_inside_boundfn() {
         let s = lower_x.to_string().unwrap();
         BQNValue::from(&s.to_uppercase()[..])
     });
-    let _ = to_upper.call1(&"hello".into());
+    assert_eq!(to_upper.call1(&"hello".into())?.to_string()?, "HELLO");
+
+    Ok(())
+}
+
+#[test]
+fn backend_calls_inside_boundfn() -> Result<()> {
+    // Reads and creates arrays, which copy through WASM memory in WASI backend
+    let sorted = BQNValue::closure1(|x| {
+        let mut v = x.to_f64_vec().unwrap();
+        v.sort_by(f64::total_cmp);
+        let s = BQNValue::from(x.to_bqnvalue_vec().unwrap().len().to_string().as_str());
+        BQNValue::from([BQNValue::from(v), s])
+    });
+    let ret = BQN!(sorted, "{𝕎𝕩}", [3, 1, 2])?.to_bqnvalue_vec()?;
+    assert_eq!(ret[0].to_f64_vec()?, [1.0, 2.0, 3.0]);
+    assert_eq!(ret[1].to_string()?, "3");
+
+    Ok(())
+}
+
+#[test]
+fn panic() -> Result<()> {
+    let f = BQNValue::fn1(|_| panic!("fn1 panic"));
+    match f.call1(&0.into()) {
+        Err(Error::BQN(e)) => assert_eq!(e.message(), "Rust panic: fn1 panic"),
+        _ => panic!("Expected an error"),
+    }
+
+    let f = BQNValue::fn2(|_, _| panic!("fn2 panic"));
+    match BQN!(1, "{𝕨 𝕏 𝕩}", f) {
+        Err(Error::BQN(e)) => assert_eq!(e.message(), "Rust panic: fn2 panic"),
+        _ => panic!("Expected an error"),
+    }
+
+    // The interpreter is still usable after a panic
+    assert_eq!(BQN!("1+1")?.to_f64()?, 2.0);
+
+    Ok(())
+}
+
+// Errors can't be caught in CBQN WASI builds
+#[cfg(not(feature = "wasi-backend"))]
+#[test]
+fn panic_catch() -> Result<()> {
+    let value = 42;
+    let f = BQNValue::closure1(move |_| panic!("closure panic {value}"));
+    assert_eq!(
+        BQN!("{𝕏⎊{𝕊: •CurrentError@} 0}", f)?.to_string()?,
+        "Rust panic: closure panic 42"
+    );
+
+    Ok(())
+}
+
+// Errors can't be caught in CBQN WASI builds
+#[cfg(not(feature = "wasi-backend"))]
+#[test]
+fn panic_inside_boundfn() -> Result<()> {
+    let outer = BQNValue::fn1(|x| {
+        let inner = BQNValue::fn1(|_| panic!("inner panic"));
+        inner.call1(x).unwrap()
+    });
+    match outer.call1(&0.into()) {
+        Err(Error::BQN(e)) => {
+            assert!(e.message().starts_with("Rust panic: "));
+            assert!(e.message().contains("inner panic"));
+        }
+        _ => panic!("Expected an error"),
+    }
+
+    // Panic in a closure that is called from a closure that catches the error
+    let inner = BQNValue::closure1(|_| panic!("inner panic"));
+    let outer = BQNValue::try_fn1(move |x| match inner.call1(x) {
+        Err(Error::BQN(e)) => Ok(BQNValue::from(e.message())),
+        _ => panic!("Expected an error"),
+    })?;
+    assert_eq!(
+        outer.call1(&0.into())?.to_string()?,
+        "Rust panic: inner panic"
+    );
+
+    // Mutable closures can be called again after a panic
+    let mut calls = 0;
+    let counter = BQNValue::closure1_mut(move |x| {
+        calls += 1;
+        if x.to_f64().unwrap() < 0.0 {
+            panic!("negative");
+        }
+        BQNValue::from(calls)
+    });
+    assert!(counter.call1(&(-1).into()).is_err());
+    assert_eq!(counter.call1(&1.into())?.to_f64()?, 2.0);
+
+    Ok(())
 }
 
 #[test]
@@ -117,3 +209,207 @@ fn boundfn_count() {
         assert_eq!(fns.boundfn_1.len(), 5);
     });
 }
+
+#[test]
+fn closure1() -> Result<()> {
+    let suffix = String::from(", world!");
+    let append = BQNValue::closure1(move |x| {
+        let s = x.to_string().unwrap();
+        BQNValue::from(s + &suffix)
+    });
+    assert_eq!(append.call1(&"hello".into())?.to_string()?, "hello, world!");
+
+    Ok(())
+}
+
+#[test]
+fn closure2() -> Result<()> {
+    let separator = '-';
+    let join = BQNValue::closure2(move |w, x| {
+        let s = format!(
+            "{}{}{}",
+            w.to_string().unwrap(),
+            separator,
+            x.to_string().unwrap()
+        );
+        BQNValue::from(s)
+    });
+    assert_eq!(
+        join.call2(&"Rust".into(), &"BQN".into())?.to_string()?,
+        "Rust-BQN"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn closure_mut() -> Result<()> {
+    let mut sum = 0.0;
+    let running_sum = BQNValue::closure1_mut(move |x| {
+        sum += x.to_f64().unwrap();
+        BQNValue::from(sum)
+    });
+    assert_eq!(
+        BQN!(running_sum, "{𝕎𝕩}¨", [1, 2, 3])?.to_f64_vec()?,
+        [1.0, 3.0, 6.0]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn closure_inside_closure() -> Result<()> {
+    let to_upper = BQNValue::closure1(|x| {
+        let to_lower = BQNValue::closure1(|x| {
+            let s = x.to_string().unwrap();
+            BQNValue::from(&s.to_lowercase()[..])
+        });
+        let lower_x = to_lower.call1(x).unwrap();
+        let s = lower_x.to_string().unwrap();
+        BQNValue::from(&s.to_uppercase()[..])
+    });
+    assert_eq!(to_upper.call1(&"hello".into())?.to_string()?, "HELLO");
+
+    Ok(())
+}
+
+#[test]
+fn closure_lifetime() -> Result<()> {
+    fn boundfn() -> Result<BQNValue> {
+        let factor = 2.0;
+        let f = BQNValue::closure1(move |x| BQNValue::from(x.to_f64().unwrap() * factor));
+        BQN!("⊢", f)
+    }
+
+    let f = boundfn()?;
+    assert_eq!(f.call1(&1.0.into())?.to_f64()?, 2.0);
+
+    Ok(())
+}
+
+#[test]
+fn closure_drop() -> Result<()> {
+    let captured = std::rc::Rc::new(());
+
+    let kept = {
+        let captured = captured.clone();
+        BQNValue::closure1(move |x| {
+            let _ = &captured;
+            x.clone()
+        })
+    };
+    let dropped = {
+        let captured = captured.clone();
+        BQNValue::closure1(move |x| {
+            let _ = &captured;
+            x.clone()
+        })
+    };
+    assert_eq!(std::rc::Rc::strong_count(&captured), 3);
+
+    drop(dropped);
+    // Registering closures collects the closures of freed functions
+    let _v = (0..64)
+        .map(|_| BQNValue::closure1(|x| x.clone()))
+        .collect::<Vec<BQNValue>>();
+    assert_eq!(std::rc::Rc::strong_count(&captured), 2);
+    assert_eq!(kept.call1(&1.into())?.to_f64()?, 1.0);
+
+    Ok(())
+}
+
+#[test]
+fn try_fn1() -> Result<()> {
+    let parse = BQNValue::try_fn1(|x| {
+        let s = x.to_string()?;
+        s.parse::<f64>()
+            .map(BQNValue::from)
+            .map_err(|_| Error::InvalidType(format!("cannot parse {s}")))
+    })?;
+    assert_eq!(parse.call1(&"1.5".into())?.to_f64()?, 1.5);
+
+    match parse.call1(&"abc".into()) {
+        Err(Error::BQN(e)) => assert_eq!(e.message(), "Invalid type: cannot parse abc"),
+        _ => panic!("Expected an error"),
+    }
+    match BQN!("{𝕏 \"abc\"}", parse) {
+        Err(Error::BQN(e)) => assert_eq!(e.message(), "Invalid type: cannot parse abc"),
+        _ => panic!("Expected an error"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn try_fn2() -> Result<()> {
+    let checked_div = BQNValue::try_fn2(|w, x| {
+        let (w, x) = (w.to_f64()?, x.to_f64()?);
+        if x == 0.0 {
+            return Err(Error::InvalidType("division by zero".into()));
+        }
+        Ok(BQNValue::from(w / x))
+    })?;
+    assert_eq!(checked_div.call2(&6.into(), &3.into())?.to_f64()?, 2.0);
+    assert!(checked_div.call2(&6.into(), &0.into()).is_err());
+
+    Ok(())
+}
+
+// Errors can't be caught in CBQN WASI builds
+#[cfg(not(feature = "wasi-backend"))]
+#[test]
+fn try_fn_catch() -> Result<()> {
+    let fail = BQNValue::try_fn1(|_| Err(Error::InvalidType("fail".into())))?;
+    assert_eq!(
+        BQN!("{𝕏⎊{𝕊: •CurrentError@} 0}", fail.clone())?.to_string()?,
+        "Invalid type: fail"
+    );
+    assert_eq!(BQN!("{𝕏⎊1 0}", fail)?.to_f64()?, 1.0);
+
+    Ok(())
+}
+
+// Errors from bound functions are returned to Rust in CBQN WASI builds, but ⎊ doesn't catch them
+#[cfg(feature = "wasi-backend")]
+#[test]
+fn try_fn_catch_wasi() -> Result<()> {
+    let fail = BQNValue::try_fn1(|_| Err(Error::InvalidType("fail".into())))?;
+    match BQN!("{𝕏⎊1 0}", fail) {
+        Err(Error::BQN(e)) => assert_eq!(e.message(), "Invalid type: fail"),
+        _ => panic!("Expected an error"),
+    }
+
+    Ok(())
+}
+
+// Errors can't be caught in CBQN WASI builds
+#[cfg(not(feature = "wasi-backend"))]
+#[test]
+fn try_fn_inside_try_fn() -> Result<()> {
+    let inner = BQNValue::try_fn1(|_| Err(Error::InvalidType("inner".into())))?;
+    let outer = BQNValue::try_fn1(move |x| inner.call1(x))?;
+    match outer.call1(&0.into()) {
+        Err(Error::BQN(e)) => assert_eq!(e.message(), "Invalid type: inner"),
+        _ => panic!("Expected an error"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn closure_on_another_thread() -> Result<()> {
+    let f = BQNValue::closure1(|_| BQNValue::from(1));
+    let ret = std::thread::spawn(move || {
+        // The other thread has closures of its own
+        let g = BQNValue::closure1(|_| BQNValue::from(2));
+        assert_eq!(g.call1(&0.into()).unwrap().to_f64().unwrap(), 2.0);
+        match f.call1(&0.into()) {
+            Err(Error::BQN(e)) => e.message().to_owned(),
+            r => panic!("Expected an error, got {r:?}"),
+        }
+    })
+    .join()
+    .unwrap();
+    assert_eq!(ret, "closure was created on another thread");
+    Ok(())
+}
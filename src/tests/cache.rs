@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn eval_cached_value() -> Result<()> {
+    // The same function is returned for every call, so its state is shared
+    for i in 1..=3 {
+        let counter = eval_cached("c←0 ⋄ {c+↩𝕩}")?;
+        assert_eq!(counter.call1(&1.into())?.to_f64()?, i as f64);
+    }
+    assert_eq!(eval_cached("1+1")?.to_f64()?, 2.0);
+    Ok(())
+}
+
+#[test]
+fn eval_cached_error() {
+    assert!(eval_cached("•").is_err());
+    assert!(eval_cached("•").is_err());
+}
+
+#[test]
+fn compiled() -> Result<()> {
+    static TABLE: Compiled = Compiled::new("×⌜");
+    let v = BQNValue::from([1, 2]);
+    assert_eq!(TABLE.call2(&v, &v)?.to_f64_vec()?, vec![1.0, 2.0, 2.0, 4.0]);
+    assert!(std::ptr::eq(TABLE.get()?, TABLE.get()?));
+
+    static INVALID: Compiled = Compiled::new("1+");
+    assert!(INVALID.get().is_err());
+    assert!(INVALID.call1(&v).is_err());
+    Ok(())
+}
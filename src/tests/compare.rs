@@ -0,0 +1,101 @@
+use super::*;
+use std::collections::{HashMap, HashSet};
+
+#[test]
+fn eq() -> Result<()> {
+    assert_eq!(
+        BQN!("⟨1, \"ab\", ⟨2‿3, 'c'⟩⟩")?,
+        BQN!("⟨1, \"ab\", ⟨2‿3, 'c'⟩⟩")?
+    );
+    assert_eq!(BQNValue::from([1.0, 2.0]), BQNValue::from(vec![1, 2]));
+    assert_ne!(BQN!("⟨1, \"ab\"⟩")?, BQN!("⟨1, \"ac\"⟩")?);
+    assert_ne!(BQN!("2‿2⥊↕4")?, BQN!("↕4")?);
+    assert_ne!(BQN!("<1")?, BQN!("1")?);
+
+    let f = eval("+")?;
+    assert_eq!(f, f.clone());
+    assert_ne!(f, eval("-")?);
+    Ok(())
+}
+
+#[test]
+fn hash() -> Result<()> {
+    let mut map = HashMap::new();
+    map.insert(BQN!("⟨1, \"ab\", ⟨2‿3⟩⟩")?, 1);
+    map.insert(BQN!("\"ab\"")?, 2);
+    assert_eq!(map.get(&BQN!("⟨1, \"ab\", ⟨2‿3⟩⟩")?), Some(&1));
+    assert_eq!(map.get(&BQNValue::from("ab")), Some(&2));
+    assert_eq!(map.get(&BQN!("⟨1, \"ab\", ⟨2‿4⟩⟩")?), None);
+
+    let set = [BQN!("1‿2")?, BQNValue::from([1.0, 2.0]), BQN!("1.5")?]
+        .into_iter()
+        .collect::<HashSet<_>>();
+    assert_eq!(set.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn ord() -> Result<()> {
+    let sorted = BQN!("∧⟨\"b\", 'a', 2, ⟨1, \"x\"⟩, \"ab\", ¯1, ⟨1, 2‿3⟩, 2‿2⥊1⟩")?;
+    let mut values =
+        BQN!("⟨\"b\", 'a', 2, ⟨1, \"x\"⟩, \"ab\", ¯1, ⟨1, 2‿3⟩, 2‿2⥊1⟩")?.to_bqnvalue_vec()?;
+    values.sort();
+    assert_eq!(values, sorted.to_bqnvalue_vec()?);
+
+    assert!(BQNValue::from(1) < BQNValue::from(2));
+    assert!(BQNValue::from(2) < BQNValue::from('a'));
+    assert!(BQNValue::from("ab") < BQNValue::from("b"));
+    assert_eq!(
+        BQN!("⟨1, \"ab\"⟩")?.cmp(&BQN!("1⊸⋈\"ab\"")?),
+        std::cmp::Ordering::Equal
+    );
+
+    // Operations are ordered after data
+    let f = eval("+")?;
+    assert!(BQN!("⟨1, 2⟩")? < f);
+    assert!(BQN!("⟨1, +⟩")? > BQN!("⟨2⟩")?);
+    Ok(())
+}
+
+#[test]
+fn ord_consistent_with_eq() -> Result<()> {
+    let f = eval("+")?;
+    let values = [
+        BQN!("1")?,
+        BQN!("<1")?,
+        BQN!("⟨1, <2⟩")?,
+        BQN!("\"ab\"")?,
+        f.clone(),
+        f,
+        eval("-")?,
+        eval("¨")?,
+        BQN!("{a⇐1}")?,
+        BQN!("{a⇐1}")?,
+        BQN!("⟨1, +⟩")?,
+    ];
+    for a in &values {
+        for b in &values {
+            assert_eq!(a.cmp(b) == std::cmp::Ordering::Equal, a == b);
+            assert_eq!(a.cmp(b), b.cmp(a).reverse());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn ord_antisymmetric() -> Result<()> {
+    // Ties in BQN's ordering and between operations are broken consistently
+    let pairs = [
+        (BQN!("1")?, BQN!("<1")?),
+        (BQN!("⟨1, 2⟩")?, BQN!("⟨1, <2⟩")?),
+        (eval("+")?, eval("-")?),
+        (eval("+")?, eval("¨")?),
+        (BQN!("{a⇐1}")?, BQN!("{a⇐1}")?),
+    ];
+    for (a, b) in &pairs {
+        assert_ne!(a, b);
+        assert_ne!(a.cmp(b), std::cmp::Ordering::Equal);
+        assert_eq!(a.cmp(b), b.cmp(a).reverse());
+    }
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use super::*;
+use std::fmt::Debug;
+
+#[derive(ToBQN, FromBQN, Debug, PartialEq)]
+struct Config {
+    name: String,
+    server_port: u16,
+    enabled: bool,
+    tags: Vec<String>,
+    timeout: Option<f64>,
+    #[bqn(rename = "addr")]
+    address: Address,
+    #[bqn(skip)]
+    cache: Vec<u8>,
+}
+
+#[derive(ToBQN, FromBQN, Debug, PartialEq)]
+#[bqn(list)]
+struct Address {
+    host: String,
+    port: i32,
+}
+
+#[derive(ToBQN, FromBQN, Debug, PartialEq)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Rect(f64, f64),
+    #[bqn(rename = "poly")]
+    Polygon {
+        sides: u32,
+        len: f64,
+    },
+}
+
+#[derive(ToBQN, FromBQN, Debug, PartialEq)]
+struct Wrapper(i32);
+
+#[derive(ToBQN, FromBQN, Debug, PartialEq)]
+struct Pair(String, char);
+
+#[derive(ToBQN, FromBQN, Debug, PartialEq)]
+struct Unit;
+
+#[derive(ToBQN, FromBQN, Debug, PartialEq)]
+struct Generic<T> {
+    items: Vec<T>,
+}
+
+fn roundtrip<T: ToBQN + FromBQN + Debug + PartialEq>(v: T) -> Result<()> {
+    assert_eq!(T::from_bqn(&v.to_bqn()?)?, v);
+    Ok(())
+}
+
+fn config() -> Config {
+    Config {
+        name: "server".into(),
+        server_port: 8080,
+        enabled: true,
+        tags: vec!["a".into(), "bc".into()],
+        timeout: None,
+        address: Address {
+            host: "localhost".into(),
+            port: 80,
+        },
+        cache: vec![],
+    }
+}
+
+#[test]
+fn derive_struct_to_namespace() -> Result<()> {
+    let v = config().to_bqn()?;
+    assert_eq!(v.bqn_type(), BQNType::Namespace);
+    assert_eq!(BQN!("{𝕩.serverport}", v.clone())?.to_f64()?, 8080.0);
+    assert_eq!(BQN!("{@≡𝕩.timeout}", v.clone())?.to_f64()?, 1.0);
+    assert_eq!(BQN!("{⊑𝕩.addr}", v.clone())?.to_string()?, "localhost");
+    assert_eq!(BQN!("{•ns.Has⟜\"cache\" 𝕩}", v)?.to_f64()?, 0.0);
+    Ok(())
+}
+
+#[test]
+fn derive_namespace_to_struct() -> Result<()> {
+    let v = BQN!(
+        r#"{
+        name⇐"server" ⋄ server_port⇐8080 ⋄ enabled⇐1 ⋄ tags⇐⟨"a","bc"⟩
+        timeout⇐@ ⋄ addr⇐⟨"localhost", 80⟩ ⋄ extra⇐"ignored"
+    }"#
+    )?;
+    assert_eq!(Config::from_bqn(&v)?, config());
+    Ok(())
+}
+
+#[test]
+fn derive_roundtrip() -> Result<()> {
+    roundtrip(config())?;
+    roundtrip(Wrapper(-7))?;
+    roundtrip(Pair("pair".into(), 'x'))?;
+    roundtrip(Unit)?;
+    roundtrip(Generic {
+        items: vec![Some(1.5), None],
+    })?;
+    Ok(())
+}
+
+#[test]
+fn derive_enum() -> Result<()> {
+    assert_eq!(Shape::Empty.to_bqn()?.to_string()?, "Empty");
+    assert_eq!(
+        BQN!(
+            "{𝕩.poly.sides}",
+            Shape::Polygon { sides: 6, len: 1.0 }.to_bqn()?
+        )?
+        .to_f64()?,
+        6.0
+    );
+    roundtrip(Shape::Empty)?;
+    roundtrip(Shape::Circle(2.0))?;
+    roundtrip(Shape::Rect(1.0, 2.0))?;
+    roundtrip(Shape::Polygon { sides: 6, len: 1.0 })?;
+    assert_eq!(Shape::from_bqn(&BQN!("\"empty\"")?)?, Shape::Empty);
+    assert_eq!(Shape::from_bqn(&BQN!("{circle⇐3}")?)?, Shape::Circle(3.0));
+    Ok(())
+}
+
+fn error_message<T: FromBQN + Debug>(v: BQNValue) -> String {
+    match T::from_bqn(&v) {
+        Err(Error::InvalidType(msg)) => msg,
+        r => panic!("expected an InvalidType error, got {r:?}"),
+    }
+}
+
+#[test]
+fn derive_error_path() -> Result<()> {
+    let v = BQN!(
+        r#"{
+        name⇐"server" ⋄ serverPort⇐8080 ⋄ enabled⇐1 ⋄ tags⇐⟨"a",2⟩
+        timeout⇐@ ⋄ addr⇐⟨"localhost", 80⟩
+    }"#
+    )?;
+    assert!(error_message::<Config>(v).starts_with("at `Config.tags[1]`: "));
+
+    let v = BQN!(
+        r#"{
+        name⇐"server" ⋄ serverPort⇐8080 ⋄ enabled⇐1 ⋄ tags⇐⟨⟩
+        timeout⇐@ ⋄ addr⇐⟨"localhost", 1.5⟩
+    }"#
+    )?;
+    assert!(error_message::<Config>(v).starts_with("at `Config.address.port`: "));
+
+    assert_eq!(
+        error_message::<Config>(BQN!("{name⇐\"server\"}")?),
+        "at `Config.server_port`: namespace has no field `serverport`"
+    );
+    assert!(error_message::<Shape>(BQN!("{poly⇐{sides⇐¯1⋄len⇐1}}")?)
+        .starts_with("at `Shape.Polygon.sides`: "));
+    assert!(error_message::<Shape>(BQN!("{rect⇐⟨1⟩}")?).starts_with("at `Shape.Rect`: "));
+    assert!(error_message::<Shape>(BQN!("\"hexagon\"")?).starts_with("at `Shape`: "));
+    assert!(error_message::<Address>(BQN!("⟨\"localhost\"⟩")?).starts_with("at `Address`: "));
+    assert!(error_message::<Unit>(BQN!("1")?).starts_with("at `Unit`: "));
+    Ok(())
+}
+
+#[derive(ToBQN, FromBQNValue, Debug, PartialEq)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+#[test]
+fn from_bqn_value_alias() -> Result<()> {
+    let p = Point { x: 1.0, y: 2.0 };
+    assert_eq!(<Point as FromBQNValue>::from_bqn(&p.to_bqn()?)?, p);
+    let v: Vec<i32> = FromBQNValue::from_bqn(&BQN!("1‿2")?)?;
+    assert_eq!(v, [1, 2]);
+    Ok(())
+}
@@ -1,14 +1,71 @@
 use crate::*;
 
+fn bqn_error(r: Result<BQNValue>) -> BQNError {
+    match r {
+        Err(Error::BQN(e)) => e,
+        r => panic!("Expected a BQN error, got {r:?}"),
+    }
+}
+
 #[test]
 fn error() {
-    let err = eval("•");
-    let error_contents = "Error: System dot with no name";
-    match err {
-        Err(Error::CBQN(stderr)) => {
-            let err = stderr.split('\n').next().unwrap();
-            assert_eq!(err, error_contents);
-        }
-        _ => panic!("Expected an error"),
-    }
+    let err = bqn_error(eval("•"));
+    assert_eq!(err.message(), "System dot with no name");
+}
+
+#[test]
+fn call1_error() {
+    let err = bqn_error(eval("{𝕩+'a'}").unwrap().call1(&'a'.into()));
+    assert!(!err.message().is_empty());
+    assert_eq!(err.span(), None);
+}
+
+#[test]
+fn call2_error() {
+    let err = bqn_error(eval("+").unwrap().call2(&'a'.into(), &'a'.into()));
+    assert!(!err.message().is_empty());
+}
+
+#[test]
+fn error_span() -> Result<()> {
+    let err = bqn_error(eval("x←1\ny←2\nx+zz"));
+    assert_eq!(err.message(), "Undefined identifier");
+    assert_eq!(
+        err.span(),
+        Some(Span {
+            line: 3,
+            start: 2,
+            end: 4
+        })
+    );
+    assert_eq!(err.to_string(), "Undefined identifier (line 3, column 3)");
+
+    let err = bqn_error(EvaluatorBuilder::new().build()?.eval("1\n⟨2,•⟩"));
+    assert_eq!(err.span().map(|s| (s.line, s.start)), Some((2, 3)));
+    Ok(())
+}
+
+// Thrown values are only printed in CBQN WASI builds
+#[cfg(not(feature = "wasi-backend"))]
+#[test]
+fn error_value() -> Result<()> {
+    let err = bqn_error(eval("⟨404, \"not found\"⟩!0"));
+    let [code, data] = <[BQNValue; 2]>::try_from(err.value().to_bqnvalue_vec()?).unwrap();
+    assert_eq!(code.to_f64()?, 404.0);
+    assert_eq!(data.to_string()?, "not found");
+    assert_eq!(err.message(), "⟨ 404 \"not found\" ⟩");
+    assert_eq!(err.span(), None);
+    Ok(())
+}
+
+// Errors can't be caught in CBQN WASI builds
+#[cfg(not(feature = "wasi-backend"))]
+#[test]
+fn error_value_through_boundfn() -> Result<()> {
+    let throw = eval("{⟨𝕩, \"data\"⟩!0}")?;
+    let f = BQNValue::try_fn1(move |x| throw.call1(x))?;
+    let err = bqn_error(f.call1(&1.into()));
+    assert_eq!(err.value().to_bqnvalue_vec()?[1].to_string()?, "data");
+    assert_eq!(BQN!("{⊑𝕏⎊{𝕊: •CurrentError@} 7}", f)?.to_f64()?, 7.0);
+    Ok(())
 }
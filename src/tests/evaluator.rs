@@ -0,0 +1,190 @@
+use super::*;
+
+#[test]
+fn custom_primitive() -> Result<()> {
+    let join = BQNValue::fn2(|w, x| {
+        let (w, x) = (w.to_string().unwrap(), x.to_string().unwrap());
+        BQNValue::from(&format!("{w}, {x}")[..])
+    });
+    let evaluator = EvaluatorBuilder::new().primitive('⍪', join).build()?;
+    assert_eq!(evaluator.eval(r#""a"⍪"b"⍪"c""#)?.to_string()?, "a, b, c");
+    assert_eq!(evaluator.eval("+´↕4")?.to_f64()?, 6.0);
+    Ok(())
+}
+
+#[test]
+fn replace_primitive() -> Result<()> {
+    let evaluator = EvaluatorBuilder::new().primitive('+', eval("-")?).build()?;
+    assert_eq!(evaluator.eval("5+3")?.to_f64()?, 2.0);
+    assert_eq!(eval("5+3")?.to_f64()?, 8.0);
+    Ok(())
+}
+
+#[test]
+fn custom_modifier() -> Result<()> {
+    let evaluator = EvaluatorBuilder::new().primitive('⍨', eval("˜")?).build()?;
+    assert_eq!(evaluator.eval("3-⍨10")?.to_f64()?, 7.0);
+    Ok(())
+}
+
+#[test]
+fn default_builder() -> Result<()> {
+    let evaluator = EvaluatorBuilder::default().build()?;
+    assert_eq!(evaluator.eval("+´↕4")?.to_f64()?, 6.0);
+    assert_eq!(evaluator.eval("•Type 1")?.to_f64()?, 1.0);
+    Ok(())
+}
+
+#[test]
+fn only_custom_primitives() -> Result<()> {
+    let evaluator = EvaluatorBuilder::new()
+        .inherit_primitives(false)
+        .primitive('÷', eval("-")?)
+        .build()?;
+    assert_eq!(evaluator.eval("÷5")?.to_f64()?, -5.0);
+    assert!(evaluator.eval("-5").is_err());
+    Ok(())
+}
+
+#[test]
+fn custom_system_values() -> Result<()> {
+    let evaluator = EvaluatorBuilder::new()
+        .system_value("range", eval("↕")?)
+        .system_value("answer_value", 42.into())
+        .build()?;
+    assert_eq!(evaluator.eval("+´•Range 4")?.to_f64()?, 6.0);
+    assert_eq!(evaluator.eval("•AnswerValue")?.to_f64()?, 42.0);
+    assert_eq!(evaluator.eval("•Type •Show")?.to_f64()?, 3.0);
+
+    let evaluator = EvaluatorBuilder::new()
+        .inherit_system(false)
+        .system_value("answer", 42.into())
+        .build()?;
+    assert_eq!(evaluator.eval("•answer")?.to_f64()?, 42.0);
+    assert!(evaluator.eval("•Show 1").is_err());
+
+    assert!(EvaluatorBuilder::new()
+        .system_value("not valid", 1.into())
+        .build()
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn evaluator_with_args() -> Result<()> {
+    let evaluator = EvaluatorBuilder::new().build()?;
+    let args = [BQNValue::from("a"), BQNValue::from("bc")];
+    assert_eq!(
+        evaluator.eval_with_args("∾•args", &args)?.to_string()?,
+        "abc"
+    );
+    assert!(evaluator.eval("•args").is_err());
+    Ok(())
+}
+
+#[test]
+fn repl_evaluator() -> Result<()> {
+    let evaluator = EvaluatorBuilder::new()
+        .repl(ReplMode::Loose)
+        .primitive('⍪', eval("∾")?)
+        .build()?;
+    evaluator.eval("a←1‿2")?;
+    assert_eq!(evaluator.eval("a⍪3")?.to_f64_vec()?, vec![1.0, 2.0, 3.0]);
+    Ok(())
+}
+
+#[test]
+fn host_system_values() -> Result<()> {
+    let evaluator = EvaluatorBuilder::new()
+        .system_fn1("double", |x| Ok(BQNValue::from(x.to_f64()? * 2.0)))
+        .system_fn2("join_with", |w, x| {
+            let sep = w.to_string()?;
+            let parts = x
+                .to_bqnvalue_vec()?
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Result<Vec<_>>>()?;
+            Ok(BQNValue::from(&parts.join(&sep)[..]))
+        })
+        .system_namespace(
+            "db",
+            [
+                ("query", BQNValue::closure1(|x| x.clone())),
+                ("row_count", 3.into()),
+            ],
+        )
+        .build()?;
+    assert_eq!(evaluator.eval("•Double 21")?.to_f64()?, 42.0);
+    assert_eq!(
+        evaluator.eval(r#"", " •JoinWith "a"‿"b""#)?.to_string()?,
+        "a, b"
+    );
+    assert_eq!(evaluator.eval(r#"•db.Query "q""#)?.to_string()?, "q");
+    assert_eq!(evaluator.eval("•db.rowCount")?.to_f64()?, 3.0);
+    assert!(evaluator.eval("•Double \"x\"").is_err());
+    Ok(())
+}
+
+#[test]
+fn should_fail_invalid_system_namespace() -> Result<()> {
+    assert!(EvaluatorBuilder::new()
+        .system_namespace("ns", [("not valid", BQNValue::null())])
+        .build()
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn capture_output() -> Result<()> {
+    let output = OutputBuffer::new();
+    let evaluator = EvaluatorBuilder::new().output(output.clone()).build()?;
+    let ret =
+        evaluator.eval(r#"•Out "héllo" ⋄ •Show 1‿2 ⋄ •term.OutRaw "ab"∾@+10 ⋄ •term.Flush 5"#)?;
+    assert_eq!(ret.to_f64()?, 5.0);
+    assert_eq!(output.take(), "héllo\n⟨ 1 2 ⟩\nab\n");
+
+    evaluator.eval("•term.OutRaw 0‿255")?;
+    assert_eq!(output.take_bytes(), vec![0, 255]);
+    assert!(evaluator.eval("•Out 5").is_err());
+    assert_eq!(output.take(), "");
+    Ok(())
+}
+
+#[test]
+fn capture_output_per_evaluator() -> Result<()> {
+    let first = OutputBuffer::new();
+    let second = OutputBuffer::new();
+    let a = EvaluatorBuilder::new()
+        .repl(ReplMode::Loose)
+        .output(first.clone())
+        .build()?;
+    let b = EvaluatorBuilder::new().output(second.clone()).build()?;
+    a.eval("x←•Show 1")?;
+    b.eval(r#"•Out "b""#)?;
+    a.eval("•Show x+1")?;
+    assert_eq!(first.take(), "1\n2\n");
+    assert_eq!(second.take(), "b\n");
+    Ok(())
+}
+
+#[test]
+fn capture_output_to_writer() -> Result<()> {
+    struct Lines(Rc<RefCell<Vec<String>>>);
+    impl std::io::Write for Lines {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let s = String::from_utf8_lossy(buf);
+            self.0.borrow_mut().extend(s.lines().map(String::from));
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let evaluator = EvaluatorBuilder::new()
+        .output(Lines(lines.clone()))
+        .build()?;
+    evaluator.eval(r#"•Out¨ "a"‿"b""#)?;
+    assert_eq!(*lines.borrow(), vec!["a", "b"]);
+    Ok(())
+}
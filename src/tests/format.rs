@@ -0,0 +1,46 @@
+use super::*;
+
+fn compact(v: &BQNValue) -> String {
+    v.format(FormatOptions::new().style(FormatStyle::Compact))
+        .to_string()
+}
+
+#[test]
+fn display() -> Result<()> {
+    let v = BQN!("1‿2‿3")?;
+    assert_eq!(format!("{v}"), "⟨ 1 2 3 ⟩");
+    assert_eq!(format!("{v}"), format!("{v:?}"));
+    Ok(())
+}
+
+#[test]
+fn format_compact() -> Result<()> {
+    assert_eq!(
+        compact(&BQN!("⟨¯1.5, ∞, 1e¯7, 'a', @, \"a\"\"b\"⟩")?),
+        "⟨ ¯1.5 ∞ 1e¯7 'a' @ \"a\"\"b\" ⟩"
+    );
+    assert_eq!(compact(&BQN!("2‿2⥊↕4")?), "2‿2⥊⟨ 0 1 2 3 ⟩");
+    assert_eq!(compact(&BQN!("<5")?), "<5");
+    assert_eq!(compact(&BQN!("⟨⟩")?), "⟨⟩");
+    assert_eq!(compact(&BQN!("⟨+, {𝕩}⌜⟩")?), "⟨ *function* *function* ⟩");
+    Ok(())
+}
+
+#[test]
+fn format_truncate() -> Result<()> {
+    let v = BQN!("↕1e7")?;
+    let options = FormatOptions::new().max_width(10);
+    assert_eq!(
+        v.format(options.clone().style(FormatStyle::Compact))
+            .to_string(),
+        "⟨ 0 1 2 3…"
+    );
+    assert_eq!(v.format(options).to_string(), "⟨ 0 1 2 3…");
+
+    let v = BQN!("1000‿2⥊↕2000")?;
+    let formatted = v.format(FormatOptions::new().max_lines(3)).to_string();
+    let lines = formatted.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[3], "…");
+    Ok(())
+}
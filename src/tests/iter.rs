@@ -0,0 +1,117 @@
+use super::*;
+
+#[test]
+fn iter() -> Result<()> {
+    let v = BQN!("↑", "abc")?;
+    let strings = v
+        .iter()?
+        .map(|e| e?.to_string())
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(strings, ["", "a", "ab", "abc"]);
+    assert_eq!(v.iter()?.len(), 4);
+    assert_eq!(v.iter()?.next_back().unwrap()?.to_string()?, "abc");
+
+    let m = BQN!("2‿2⥊1‿'a'‿2‿'b'")?;
+    let types = m
+        .iter()?
+        .map(|e| e?.try_bqn_type())
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(
+        types,
+        [
+            BQNType::Number,
+            BQNType::Character,
+            BQNType::Number,
+            BQNType::Character
+        ]
+    );
+    assert_eq!(BQN!("⟨⟩")?.iter()?.count(), 0);
+    assert!(BQN!("5")?.iter().is_err());
+    Ok(())
+}
+
+#[test]
+fn major_cells() -> Result<()> {
+    let m = BQN!("3‿2⥊↕6")?;
+    let rows = m
+        .major_cells()?
+        .map(|r| r?.to_f64_vec())
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(rows, [[0.0, 1.0], [2.0, 3.0], [4.0, 5.0]]);
+    assert_eq!(m.major_cells()?.next_back().unwrap()?.shape(), [2]);
+
+    let cells = BQN!("1‿2‿3")?.major_cells()?.collect::<Result<Vec<_>>>()?;
+    assert_eq!(cells.len(), 3);
+    assert_eq!(cells[1].rank(), 0);
+    assert_eq!(cells[1], BQN!("<2")?);
+    assert_eq!(BQN!("0‿3⥊0")?.major_cells()?.len(), 0);
+    Ok(())
+}
+
+#[test]
+fn should_fail_major_cells() -> Result<()> {
+    assert!(BQN!("<5")?.major_cells().is_err());
+    assert!(BQN!("5")?.major_cells().is_err());
+    Ok(())
+}
+
+#[test]
+fn iter_f64() -> Result<()> {
+    let v = BQN!("2‿2⥊1.5‿2‿¯3‿4")?;
+    assert_eq!(
+        v.iter_f64()?.collect::<Result<Vec<_>>>()?,
+        [1.5, 2.0, -3.0, 4.0]
+    );
+    assert_eq!(v.iter_f64()?.next_back().transpose()?, Some(4.0));
+    assert_eq!(BQN!("⟨1, 2⟩")?.iter_f64()?.sum::<Result<f64>>()?, 3.0);
+    assert!(BQN!("⟨1, 'a'⟩")?.iter_f64().is_err());
+    assert!(BQN!("\"ab\"")?.iter_f64().is_err());
+    Ok(())
+}
+
+#[test]
+fn iter_f64_chunks() -> Result<()> {
+    // Longer than a chunk, and read from both ends
+    for code in ["↕10000", "2÷˜↕10000", "10000⥊↕100", "100‿100⥊↕10000"] {
+        let v = BQN!(code)?;
+        let expected = v.to_f64_vec()?;
+        assert_eq!(v.iter_f64()?.collect::<Result<Vec<_>>>()?, expected);
+
+        let mut it = v.iter_f64()?;
+        let mut front = vec![];
+        let mut back = vec![];
+        loop {
+            match (it.next(), it.next_back()) {
+                (Some(f), Some(b)) => {
+                    front.push(f?);
+                    back.push(b?);
+                }
+                (Some(f), None) => front.push(f?),
+                (None, _) => break,
+            }
+        }
+        front.extend(back.into_iter().rev());
+        assert_eq!(front, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn iter_chars() -> Result<()> {
+    let v = BQN!("\"hello\"")?;
+    assert_eq!(v.iter_chars()?.collect::<Result<String>>()?, "hello");
+    assert_eq!(v.iter_chars()?.rev().collect::<Result<String>>()?, "olleh");
+    assert_eq!(
+        BQN!("\"a\"∾(@+55296)∾\"b\"")?
+            .iter_chars()?
+            .collect::<Result<String>>()?,
+        "ab"
+    );
+    let long = BQN!("5000⥊\"abc\"")?;
+    assert_eq!(
+        long.iter_chars()?.rev().collect::<Result<String>>()?,
+        long.to_string()?.chars().rev().collect::<String>()
+    );
+    assert!(BQN!("1‿2")?.iter_chars().is_err());
+    Ok(())
+}
@@ -1,10 +1,24 @@
 mod arrconv;
-#[cfg(not(feature = "wasi-backend"))]
 mod boundfn;
+mod cache;
+mod compare;
+#[cfg(feature = "derive")]
+mod derive;
 mod error;
+mod evaluator;
+mod format;
 mod from;
 mod gen;
+mod iter;
+mod namespace;
+#[cfg(feature = "ndarray")]
+mod ndarray;
 mod null;
+mod ops;
+mod path;
+#[cfg(feature = "serde")]
+mod serde;
+mod session;
 mod to;
 
 use crate::*;
@@ -26,6 +40,46 @@ fn call2() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn apply_mod1() -> Result<()> {
+    let m = eval("{𝕨𝔽˜𝕩}")?;
+    let f = m.apply_mod1(&eval("-")?)?;
+    assert_eq!(f.call2(&1.into(), &3.into())?.to_f64()?, 2.0);
+
+    let table = eval("⌜")?.apply_mod1(&eval("×")?)?;
+    let ret = table.call2(&[1, 2].into(), &[3, 4].into())?;
+    assert_eq!(ret.shape(), vec![2, 2]);
+    assert_eq!(ret.to_f64_vec()?, vec![3.0, 4.0, 6.0, 8.0]);
+
+    // Operands don't need to be functions
+    let ret = eval("˙")?.apply_mod1(&5.into())?.call1(&0.into())?;
+    assert_eq!(ret.to_f64()?, 5.0);
+    Ok(())
+}
+
+#[test]
+fn apply_mod2() -> Result<()> {
+    let under = eval("{𝔾⁼𝔽𝔾𝕩}")?;
+    let f = under.apply_mod2(&eval("1⊸↓")?, &eval("⌽")?)?;
+    assert_eq!(f.call1(&"hello".into())?.to_string()?, "hell");
+
+    let atop = eval("∘")?.apply_mod2(&eval("-")?, &eval("+")?)?;
+    assert_eq!(atop.call2(&1.into(), &2.into())?.to_f64()?, -3.0);
+    Ok(())
+}
+
+#[test]
+fn apply_mod_invalid_type() -> Result<()> {
+    let f = eval("+")?;
+    assert!(matches!(f.apply_mod1(&f), Err(Error::InvalidType(_))));
+    assert!(matches!(f.apply_mod2(&f, &f), Err(Error::InvalidType(_))));
+    assert!(matches!(
+        eval("¨")?.apply_mod2(&f, &f),
+        Err(Error::InvalidType(_))
+    ));
+    Ok(())
+}
+
 #[test]
 fn fixed_size_array() -> Result<()> {
     let f = eval("+´")?;
@@ -57,12 +111,12 @@ fn namespace() -> Result<()> {
 
     assert!(ns.has_field("a")?);
     assert!(ns.get_field("a")?.is_some());
-    assert!(!ns.has_field("A")?);
-    assert!(ns.get_field("A")?.is_none());
+    assert!(ns.has_field("A")?);
+    assert!(ns.get_field("A")?.is_some());
     assert!(ns.has_field("b")?);
     assert!(ns.get_field("b")?.is_some());
-    assert!(!ns.has_field("B")?);
-    assert!(ns.get_field("B")?.is_none());
+    assert!(ns.has_field("B")?);
+    assert!(ns.get_field("B")?.is_some());
     assert!(!ns.has_field("c")?);
     assert!(ns.get_field("c")?.is_none());
 
@@ -114,3 +168,47 @@ fn shape() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn fallible_accessors() -> Result<()> {
+    let v = BQN!("2‿3⥊↕6")?;
+    assert_eq!(v.try_rank()?, 2);
+    assert_eq!(v.try_shape()?, vec![2, 3]);
+    assert_eq!(v.try_bqn_type()?, BQNType::Array);
+    assert_eq!(BQN!(v.try_clone()?, "≡", v)?.to_f64()?, 1.0);
+    assert_eq!(BQN!("@≡", BQNValue::try_null()?)?.to_f64()?, 1.0);
+    Ok(())
+}
+
+#[test]
+fn fallible_constructors() -> Result<()> {
+    try_init()?;
+    // Initializing again is a no-op
+    try_init()?;
+    assert_eq!(BQNValue::try_from_f64(1.5)?.to_f64()?, 1.5);
+    assert_eq!(BQNValue::try_from_char('x')?.to_char()?, Some('x'));
+    assert_eq!(BQNValue::try_from_str("hello")?.to_string()?, "hello");
+    assert_eq!(
+        BQN!("≍", BQNValue::try_from_strs(&["a", "bc"])?)?.shape(),
+        vec![1, 2]
+    );
+    assert_eq!(
+        BQNValue::try_from_i8_slice(&[1, -2])?.to_i8_vec()?,
+        vec![1, -2]
+    );
+    assert_eq!(
+        BQNValue::try_from_i16_slice(&[300])?.to_i16_vec()?,
+        vec![300]
+    );
+    assert_eq!(
+        BQNValue::try_from_i32_slice(&[70000])?.to_i32_vec()?,
+        vec![70000]
+    );
+    assert_eq!(
+        BQNValue::try_from_f64_slice(&[0.5])?.to_f64_vec()?,
+        vec![0.5]
+    );
+    let list = BQNValue::try_from_values(vec![1.into(), "a".into()])?;
+    assert_eq!(BQN!("•Type¨", list)?.to_f64_vec()?, vec![1.0, 0.0]);
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use super::*;
+
+#[test]
+fn namespace_from() -> Result<()> {
+    let ns = BQNValue::namespace_from([
+        ("name", BQNValue::from("tree")),
+        ("Max_Depth", BQNValue::from(3)),
+        ("items", BQNValue::from([1, 2])),
+    ])?;
+    assert_eq!(ns.bqn_type(), BQNType::Namespace);
+    assert_eq!(BQN!("{𝕩.name}", ns.clone())?.to_string()?, "tree");
+    assert_eq!(BQN!("{𝕩.max_depth}", ns.clone())?.to_f64()?, 3.0);
+    assert_eq!(BQN!("{+´𝕩.items}", ns)?.to_f64()?, 3.0);
+
+    let fields = vec![(String::from("x"), BQNValue::from(1))];
+    assert!(BQNValue::namespace_from(fields)?.has_field("x")?);
+    assert!(BQNValue::namespace_from(Vec::<(&str, BQNValue)>::new())?
+        .fields()?
+        .is_empty());
+    Ok(())
+}
+
+#[test]
+fn namespace_from_many_field_lists() -> Result<()> {
+    // More field lists than fit in the constructor cache
+    for i in 0..1100 {
+        let ns = BQNValue::namespace_from([(format!("f{i}"), BQNValue::from(i))])?;
+        assert_eq!(ns.get_field(&format!("f{i}"))?.unwrap().to_f64()?, i as f64);
+    }
+    Ok(())
+}
+
+#[test]
+fn should_fail_namespace_from() {
+    assert!(BQNValue::namespace_from([("a b", BQNValue::from(1))]).is_err());
+    assert!(BQNValue::namespace_from([("", BQNValue::from(1))]).is_err());
+    assert!(BQNValue::namespace_from([("1a", BQNValue::from(1))]).is_err());
+    assert!(
+        BQNValue::namespace_from([("max_depth", BQNValue::from(1)), ("maxDepth", 2.into())])
+            .is_err()
+    );
+}
+
+#[test]
+fn fields() -> Result<()> {
+    let ns = BQN!("{a⇐1 ⋄ hidden←2 ⋄ Fn⇐{𝕩} ⋄ long_name⇐\"x\"}")?;
+    let mut fields = ns.fields()?;
+    fields.sort_by(|a, b| a.0.cmp(&b.0));
+    let names = fields.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["a", "fn", "longname"]);
+    assert_eq!(fields[0].1.to_f64()?, 1.0);
+    assert_eq!(fields[1].1.bqn_type(), BQNType::Function);
+    assert_eq!(fields[2].1.to_string()?, "x");
+
+    let roundtrip = BQNValue::namespace_from(ns.fields()?)?;
+    assert_eq!(BQN!("{𝕩.LongName}", roundtrip)?.to_string()?, "x");
+
+    assert!(BQNValue::from(1).fields().is_err());
+    Ok(())
+}
+
+#[test]
+fn normalized_field_lookup() -> Result<()> {
+    for src in ["{MaxDepth⇐3}", "{max_depth⇐3}", "{maxdepth⇐3}"] {
+        let ns = BQN!(src)?;
+        for name in [
+            "MaxDepth",
+            "max_depth",
+            "maxdepth",
+            "MAX_DEPTH",
+            "_max_depth_",
+        ] {
+            assert!(ns.has_field(name)?);
+            assert_eq!(ns.get_field(name)?.unwrap().to_f64()?, 3.0);
+        }
+        assert!(!ns.has_field("depth")?);
+        assert!(ns.get_field("depth")?.is_none());
+    }
+    Ok(())
+}
+
+#[test]
+fn should_fail_invalid_field_name() -> Result<()> {
+    let ns = BQN!("{a⇐1}")?;
+    for name in ["", "_", "a b", "1a", "a-b", "ä"] {
+        assert!(matches!(ns.has_field(name), Err(Error::InvalidType(_))));
+        assert!(matches!(ns.get_field(name), Err(Error::InvalidType(_))));
+    }
+    Ok(())
+}
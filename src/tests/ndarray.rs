@@ -0,0 +1,89 @@
+use super::*;
+use ::ndarray::{arr2, ArrayD, IxDyn};
+
+#[test]
+fn from_f64_array() -> Result<()> {
+    let arr = arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]).into_dyn();
+    let v = BQNValue::from(arr);
+    assert_eq!(v.shape(), vec![2, 3]);
+    assert_eq!(BQN!("+˝", v)?.to_f64_vec()?, vec![5.0, 7.0, 9.0]);
+    Ok(())
+}
+
+#[test]
+fn from_transposed_array() -> Result<()> {
+    let arr = arr2(&[[1, 2, 3], [4, 5, 6]]).into_dyn().reversed_axes();
+    let v = BQNValue::from(arr);
+    assert_eq!(v.shape(), vec![3, 2]);
+    assert_eq!(v.to_i32_vec()?, vec![1, 4, 2, 5, 3, 6]);
+    Ok(())
+}
+
+#[test]
+fn from_integer_arrays() -> Result<()> {
+    let shape = IxDyn(&[2, 2, 2]);
+    let i32s = ArrayD::from_shape_fn(shape.clone(), |i| (i[0] * 4 + i[1] * 2 + i[2]) as i32);
+    let i16s = i32s.mapv(|v| v as i16);
+    let i8s = i32s.mapv(|v| v as i8);
+    for v in [
+        BQNValue::from(i32s),
+        BQNValue::from(i16s),
+        BQNValue::from(i8s),
+    ] {
+        assert_eq!(BQN!("≡⟜(2‿2‿2⥊↕8)", v)?.to_f64()?, 1.0);
+    }
+    Ok(())
+}
+
+#[test]
+fn from_char_array() -> Result<()> {
+    let arr = ArrayD::from_shape_vec(IxDyn(&[2, 2]), vec!['a', 'b', 'c', 'd']).unwrap();
+    let v = BQNValue::from(arr);
+    assert_eq!(BQN!("≡⟜(2‿2⥊\"abcd\")", v)?.to_f64()?, 1.0);
+    Ok(())
+}
+
+#[test]
+fn scalar_array() -> Result<()> {
+    let arr = ArrayD::from_elem(IxDyn(&[]), 5.0);
+    let v = BQNValue::from(arr);
+    assert_eq!(v.rank(), 0);
+    assert_eq!(
+        ArrayD::<f64>::try_from(&v)?,
+        ArrayD::from_elem(IxDyn(&[]), 5.0)
+    );
+    Ok(())
+}
+
+#[test]
+fn to_array() -> Result<()> {
+    let v = BQN!("2‿3‿4⥊↕24")?;
+    let arr = ArrayD::<f64>::try_from(&v)?;
+    assert_eq!(arr.shape(), &[2, 3, 4]);
+    assert_eq!(arr[[1, 2, 3]], 23.0);
+
+    let arr = ArrayD::<i32>::try_from(&v)?;
+    assert_eq!(arr[[1, 0, 2]], 14);
+    assert!(ArrayD::<i8>::try_from(&BQN!("2‿2⥊1000")?).is_err());
+
+    let arr = ArrayD::<char>::try_from(&BQN!("3‿2⥊\"abcdef\"")?)?;
+    assert_eq!(arr.shape(), &[3, 2]);
+    assert_eq!(arr[[2, 0]], 'e');
+    Ok(())
+}
+
+#[test]
+fn array_roundtrip() -> Result<()> {
+    let arr = ArrayD::from_shape_fn(IxDyn(&[3, 1, 2]), |i| (i[0] + i[2]) as i16);
+    let v = BQNValue::from(arr.clone());
+    assert_eq!(ArrayD::<i16>::try_from(&v)?, arr);
+    Ok(())
+}
+
+#[test]
+fn should_fail_to_array() -> Result<()> {
+    assert!(ArrayD::<f64>::try_from(&BQN!("\"abc\"")?).is_err());
+    assert!(ArrayD::<char>::try_from(&BQN!("1‿2")?).is_err());
+    assert!(ArrayD::<f64>::try_from(&BQN!("5")?).is_err());
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use super::*;
+
+#[test]
+fn arithmetic() -> Result<()> {
+    let a = BQNValue::from([1.0, 2.0, 3.0]);
+    let b = BQNValue::from(2);
+    assert_eq!((&a + &b)?.to_f64_vec()?, vec![3.0, 4.0, 5.0]);
+    assert_eq!((&a - &b)?.to_f64_vec()?, vec![-1.0, 0.0, 1.0]);
+    assert_eq!((&a * &a)?.to_f64_vec()?, vec![1.0, 4.0, 9.0]);
+    assert_eq!((&b / &a)?.to_f64_vec()?, vec![2.0, 1.0, 2.0 / 3.0]);
+    assert_eq!((-&a)?.to_f64_vec()?, vec![-1.0, -2.0, -3.0]);
+    assert_eq!((&BQNValue::from('a') + &b)?.to_char()?, Some('c'));
+    Ok(())
+}
+
+#[test]
+fn logic() -> Result<()> {
+    let a = BQNValue::from([0, 1, 0, 1]);
+    let b = BQNValue::from([0, 0, 1, 1]);
+    assert_eq!((&a & &b)?.to_bool_vec()?, vec![false, false, false, true]);
+    assert_eq!((&a | &b)?.to_bool_vec()?, vec![false, true, true, true]);
+    assert_eq!((!&a)?.to_bool_vec()?, vec![true, false, true, false]);
+    Ok(())
+}
+
+#[test]
+fn broadcasting() -> Result<()> {
+    let m = BQN!("2‿3⥊↕6")?;
+    let sum = (&m + &BQNValue::from([10, 20]))?;
+    assert_eq!(sum.shape(), vec![2, 3]);
+    assert_eq!(sum.to_f64_vec()?, vec![10.0, 11.0, 12.0, 23.0, 24.0, 25.0]);
+
+    let nested = (&BQN!("⟨1, 2‿3⟩")? * &BQNValue::from(2))?;
+    assert_eq!(nested, BQN!("⟨2, 4‿6⟩")?);
+    Ok(())
+}
+
+#[test]
+fn op_errors() -> Result<()> {
+    assert!((&BQNValue::from('a') + &BQNValue::from('b')).is_err());
+    assert!((&BQNValue::from([1, 2]) + &BQNValue::from([1, 2, 3])).is_err());
+    assert!((-&BQNValue::from("ab")).is_err());
+    Ok(())
+}
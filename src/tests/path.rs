@@ -0,0 +1,49 @@
+use super::*;
+
+#[test]
+fn at() -> Result<()> {
+    let v = BQN!(
+        "{server⇐{host⇐\"localhost\" ⋄ Ports⇐80‿443‿8080} ⋄ users⇐⟨{name⇐\"a\"}, {name⇐\"b\"}⟩}"
+    )?;
+    assert_eq!(v.at("server.ports[2]")?.unwrap().to_f64()?, 8080.0);
+    assert_eq!(v.at("server.Ports[0]")?.unwrap().to_f64()?, 80.0);
+    assert_eq!(v.at("server.host")?.unwrap().to_string()?, "localhost");
+    assert_eq!(v.at("users[1].name")?.unwrap().to_string()?, "b");
+    assert_eq!(v.at("server")?.unwrap().bqn_type(), BQNType::Namespace);
+
+    let m = BQN!("2‿2⥊⟨1‿2, 3, 4, 5⟩")?;
+    assert_eq!(m.at("[0][1]")?.unwrap().to_f64()?, 2.0);
+    assert_eq!(m.at("[3]")?.unwrap().to_f64()?, 5.0);
+    Ok(())
+}
+
+#[test]
+fn at_missing() -> Result<()> {
+    let v = BQN!("{server⇐{ports⇐80‿443} ⋄ hidden←1}")?;
+    assert!(v.at("client")?.is_none());
+    assert!(v.at("client.ports[0]")?.is_none());
+    assert!(v.at("server.ports[2]")?.is_none());
+    assert!(v.at("hidden")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn should_fail_at() -> Result<()> {
+    let v = BQN!("{server⇐{host⇐\"localhost\" ⋄ port⇐80}}")?;
+    assert!(v.at("server.port[0]").is_err());
+    assert!(v.at("server.port.number").is_err());
+    assert!(v.at("[0]").is_err());
+    for path in [
+        "",
+        ".server",
+        "server.",
+        "server..port",
+        "server[",
+        "server[x]",
+        "server[-1]",
+        "server[0]port",
+    ] {
+        assert!(v.at(path).is_err(), "{path}");
+    }
+    Ok(())
+}
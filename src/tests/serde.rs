@@ -0,0 +1,171 @@
+use super::*;
+use ::serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Config {
+    name: String,
+    server_port: u16,
+    ratio: f64,
+    enabled: bool,
+    tags: Vec<String>,
+    timeout: Option<f64>,
+    inner: Inner,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Inner {
+    id: i64,
+    initial: char,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Rect(f64, f64),
+    Polygon { sides: u32, len: f64 },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Wrapper(i32);
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Pair(String, i8);
+
+fn roundtrip<T: Serialize + ::serde::de::DeserializeOwned + Debug + PartialEq>(v: T) -> Result<()> {
+    let bqn = to_value(&v)?;
+    assert_eq!(from_value::<T>(&bqn)?, v);
+    Ok(())
+}
+
+fn config() -> Config {
+    Config {
+        name: "server".into(),
+        server_port: 8080,
+        ratio: 0.5,
+        enabled: true,
+        tags: vec!["a".into(), "bc".into()],
+        timeout: None,
+        inner: Inner {
+            id: -3,
+            initial: 'x',
+        },
+    }
+}
+
+#[test]
+fn struct_to_namespace() -> Result<()> {
+    let v = to_value(&config())?;
+    assert_eq!(v.bqn_type(), BQNType::Namespace);
+    assert_eq!(BQN!("{𝕩.name}", v.clone())?.to_string()?, "server");
+    assert_eq!(BQN!("{𝕩.serverport}", v.clone())?.to_f64()?, 8080.0);
+    assert_eq!(BQN!("{𝕩.enabled}", v.clone())?.to_f64()?, 1.0);
+    assert_eq!(BQN!("{@≡𝕩.timeout}", v.clone())?.to_f64()?, 1.0);
+    assert_eq!(BQN!("{𝕩.inner.initial}", v)?.to_char()?, Some('x'));
+    Ok(())
+}
+
+#[test]
+fn namespace_to_struct() -> Result<()> {
+    let v = BQN!(
+        r#"{
+        name⇐"server" ⋄ serverPort⇐8080 ⋄ ratio⇐0.5 ⋄ enabled⇐1 ⋄ tags⇐⟨"a","bc"⟩
+        timeout⇐@ ⋄ inner⇐{id⇐¯3 ⋄ initial⇐'x'} ⋄ extra⇐"ignored"
+    }"#
+    )?;
+    assert_eq!(from_value::<Config>(&v)?, config());
+    Ok(())
+}
+
+#[test]
+fn list_to_struct() -> Result<()> {
+    let v = BQN!("⟨5, 'y'⟩")?;
+    assert_eq!(
+        from_value::<Inner>(&v)?,
+        Inner {
+            id: 5,
+            initial: 'y'
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn serde_roundtrip() -> Result<()> {
+    roundtrip(config())?;
+    roundtrip(Config {
+        timeout: Some(1.5),
+        tags: vec![],
+        ..config()
+    })?;
+    roundtrip(vec![1.5, -2.0, 3.25])?;
+    roundtrip(vec![vec![1u8, 2], vec![], vec![3]])?;
+    roundtrip((1, "two".to_string(), '3'))?;
+    roundtrip(Some(vec![Some(1), None]))?;
+    roundtrip(Wrapper(-7))?;
+    roundtrip(Pair("pair".into(), 8))?;
+    roundtrip(String::new())?;
+    roundtrip(())?;
+    roundtrip(HashMap::from([
+        ("one".to_string(), 1),
+        ("two".to_string(), 2),
+    ]))?;
+    Ok(())
+}
+
+#[test]
+fn enum_roundtrip() -> Result<()> {
+    assert_eq!(to_value(&Shape::Empty)?.to_string()?, "Empty");
+    roundtrip(Shape::Empty)?;
+    roundtrip(Shape::Circle(2.0))?;
+    roundtrip(Shape::Rect(1.0, 2.0))?;
+    roundtrip(Shape::Polygon { sides: 6, len: 1.0 })?;
+    assert_eq!(
+        from_value::<Shape>(&BQN!("{circle⇐3}")?)?,
+        Shape::Circle(3.0)
+    );
+    Ok(())
+}
+
+#[test]
+fn matrix_to_nested_vec() -> Result<()> {
+    let v = BQN!("2‿3⥊↕6")?;
+    assert_eq!(
+        from_value::<Vec<Vec<i32>>>(&v)?,
+        vec![vec![0, 1, 2], vec![3, 4, 5]]
+    );
+    Ok(())
+}
+
+#[test]
+fn large_integers() -> Result<()> {
+    roundtrip(1i64 << 53)?;
+    roundtrip(-(1i64 << 53))?;
+    roundtrip(1u64 << 60)?;
+    roundtrip(1u64 << 63)?;
+    roundtrip(u64::MAX - (1 << 11) + 1)?;
+    roundtrip(i64::MIN)?;
+    assert!(from_value::<u64>(&BQN!("2⋆64")?).is_err());
+    assert!(from_value::<i64>(&BQN!("2⋆63")?).is_err());
+    assert!(from_value::<u64>(&BQN!("¯1")?).is_err());
+    assert!(to_value(&((1i64 << 53) + 1)).is_err());
+    assert!(to_value(&i64::MAX).is_err());
+    assert!(to_value(&u64::MAX).is_err());
+    assert!(to_value(&vec![1u64, (1 << 53) + 1]).is_err());
+    Ok(())
+}
+
+#[test]
+fn should_fail_serde() -> Result<()> {
+    assert!(from_value::<u8>(&BQN!("256")?).is_err());
+    assert!(from_value::<i32>(&BQN!("1.5")?).is_err());
+    assert!(from_value::<bool>(&BQN!("2")?).is_err());
+    assert!(from_value::<String>(&BQN!("1‿2")?).is_err());
+    assert!(from_value::<Inner>(&BQN!("{id⇐1}")?).is_err());
+    assert!(to_value(&HashMap::from([(1, 2)])).is_err());
+    assert!(to_value(&HashMap::from([("not valid", 2)])).is_err());
+    assert!(to_value(&HashMap::from([("a_b", 1), ("ab", 2)])).is_err());
+    Ok(())
+}
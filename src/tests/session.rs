@@ -0,0 +1,44 @@
+use super::*;
+
+#[test]
+fn session_shares_scope() -> Result<()> {
+    let session = Session::new()?;
+    session.eval("a←1")?;
+    session.eval("F←{𝕩+a}")?;
+    assert_eq!(session.eval("F 2")?.to_f64()?, 3.0);
+    session.eval("a←10")?;
+    assert_eq!(session.eval("a")?.to_f64()?, 10.0);
+    Ok(())
+}
+
+#[test]
+fn independent_sessions() -> Result<()> {
+    let first = Session::new()?;
+    let second = Session::new()?;
+    first.eval("a←1")?;
+    second.eval("a←2")?;
+    assert_eq!(first.eval("a")?.to_f64()?, 1.0);
+    assert_eq!(second.eval("a")?.to_f64()?, 2.0);
+    assert!(eval("a").is_err());
+    Ok(())
+}
+
+#[test]
+fn strict_session() -> Result<()> {
+    let session = Session::with_mode(ReplMode::Strict)?;
+    session.eval("a←↕2")?;
+    assert!(session.eval("a←↕3").is_err());
+    session.eval("a↩↕3")?;
+    assert_eq!(session.eval("a")?.to_f64_vec()?, vec![0.0, 1.0, 2.0]);
+    Ok(())
+}
+
+#[test]
+fn session_values_outlive_evaluation() -> Result<()> {
+    let session = Session::new()?;
+    let f = session.eval("n←0 ⋄ {n+↩𝕩}")?;
+    f.call1(&5.into())?;
+    f.call1(&2.into())?;
+    assert_eq!(session.eval("n")?.to_f64()?, 7.0);
+    Ok(())
+}
@@ -28,6 +28,64 @@ fn elt_unk_to_f64_vec() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn to_i32_vec() -> Result<()> {
+    assert_eq!(BQN!("¯2+↕5")?.to_i32_vec()?, vec![-2, -1, 0, 1, 2]);
+    assert_eq!(BQN!("1e6×1‿2")?.to_i32_vec()?, vec![1_000_000, 2_000_000]);
+    assert_eq!(BQN!("2‿2⥊↕4")?.to_i32_vec()?, vec![0, 1, 2, 3]);
+    assert!(BQN!("1e10‿1")?.to_i32_vec().is_err());
+    assert!(BQN!("1.5‿1")?.to_i32_vec().is_err());
+    Ok(())
+}
+
+#[test]
+fn to_i16_vec() -> Result<()> {
+    assert_eq!(BQN!("¯300‿300")?.to_i16_vec()?, vec![-300, 300]);
+    assert!(BQN!("⟨1e5⟩")?.to_i16_vec().is_err());
+    Ok(())
+}
+
+#[test]
+fn to_i8_vec() -> Result<()> {
+    assert_eq!(BQN!("¯128‿127")?.to_i8_vec()?, vec![-128, 127]);
+    assert!(BQN!("⟨128⟩")?.to_i8_vec().is_err());
+    Ok(())
+}
+
+#[test]
+fn to_u8_vec() -> Result<()> {
+    assert_eq!(BQN!("0‿255")?.to_u8_vec()?, vec![0, 255]);
+    assert!(BQN!("⟨¯1⟩")?.to_u8_vec().is_err());
+    assert!(BQN!("⟨256⟩")?.to_u8_vec().is_err());
+    Ok(())
+}
+
+#[test]
+fn to_bool_vec() -> Result<()> {
+    assert_eq!(BQN!("0‿1‿1")?.to_bool_vec()?, vec![false, true, true]);
+    assert_eq!(
+        BQN!("=⌜˜↕2")?.to_bool_vec()?,
+        vec![true, false, false, true]
+    );
+    assert!(BQN!("0‿1‿2")?.to_bool_vec().is_err());
+    Ok(())
+}
+
+#[test]
+fn elt_unk_to_i32_vec() -> Result<()> {
+    let ret = eval(r#"1↓"abc"∾2‿∘⥊↕6"#)?;
+    assert_eq!(ret.to_i32_vec()?, vec![0, 1, 2, 3, 4, 5]);
+    assert!(eval("1↓'a'∾1.5‿2")?.to_i32_vec().is_err());
+    Ok(())
+}
+
+#[test]
+fn should_fail_char_array_to_i32_vec() -> Result<()> {
+    assert!(BQN!("\"abc\"")?.to_i32_vec().is_err());
+    assert!(BQN!("5")?.to_i32_vec().is_err());
+    Ok(())
+}
+
 #[test]
 fn to_bqnvalue_vec() -> Result<()> {
     let strings = BQN!("↑", "hello")?
@@ -46,9 +104,119 @@ fn should_fail_number_to_bqnvalue_vec() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn pick() -> Result<()> {
+    let v = BQN!("↑", "hello")?;
+    assert_eq!(v.pick(0)?.to_string()?, "");
+    assert_eq!(v.pick(3)?.to_string()?, "hel");
+    assert_eq!(BQN!("2‿3⥊↕6")?.pick(4)?.to_f64()?, 4.0);
+    assert_eq!(BQN!("<5")?.pick(0)?.to_f64()?, 5.0);
+    Ok(())
+}
+
+#[test]
+fn should_fail_pick() -> Result<()> {
+    let v = BQN!("↕3")?;
+    assert!(matches!(v.pick(3), Err(Error::IndexOutOfBounds(_))));
+    assert!(matches!(
+        BQN!("⟨⟩")?.pick(0),
+        Err(Error::IndexOutOfBounds(_))
+    ));
+    assert!(matches!(BQN!("3")?.pick(0), Err(Error::InvalidType(_))));
+    Ok(())
+}
+
+#[test]
+fn get() -> Result<()> {
+    let v = BQN!("2‿3‿4⥊↕24")?;
+    assert_eq!(v.get(&[0, 0, 0])?.unwrap().to_f64()?, 0.0);
+    assert_eq!(v.get(&[1, 2, 3])?.unwrap().to_f64()?, 23.0);
+    assert_eq!(v.get(&[1, 0, 2])?.unwrap().to_f64()?, 14.0);
+    assert!(v.get(&[2, 0, 0])?.is_none());
+    assert!(v.get(&[0, 3, 0])?.is_none());
+    assert!(BQN!("0‿3⥊0")?.get(&[0, 0])?.is_none());
+    assert_eq!(BQN!("<'a'")?.get(&[])?.unwrap().to_char()?, Some('a'));
+    Ok(())
+}
+
+#[test]
+fn should_fail_get() -> Result<()> {
+    let v = BQN!("2‿3⥊↕6")?;
+    assert!(v.get(&[1]).is_err());
+    assert!(v.get(&[0, 0, 0]).is_err());
+    assert!(BQN!("{a⇐1}")?.get(&[0]).is_err());
+    Ok(())
+}
+
 #[test]
 fn elt_unk_to_string() -> Result<()> {
     let v = BQN!(r#"1↓0∾"aaa""#)?;
     assert_eq!(v.to_string()?, "aaa");
     Ok(())
 }
+
+#[test]
+fn try_into_nested() -> Result<()> {
+    let v = BQN!("⟨1‿\"one\", 2‿\"two\"⟩")?;
+    let pairs: Vec<(f64, String)> = (&v).try_into()?;
+    assert_eq!(pairs, vec![(1.0, "one".into()), (2.0, "two".into())]);
+
+    let v = BQN!("⟨1, @, 3⟩")?;
+    let opts: Vec<Option<u8>> = (&v).try_into()?;
+    assert_eq!(opts, vec![Some(1), None, Some(3)]);
+
+    let (a, b, c): (char, bool, i64) = (&BQN!("⟨'x', 1, ¯5⟩")?).try_into()?;
+    assert_eq!((a, b, c), ('x', true, -5));
+    Ok(())
+}
+
+#[test]
+fn namespace_to_hashmap() -> Result<()> {
+    let v = BQN!("{one⇐⟨1⟩ ⋄ Two_Three⇐2‿3}")?;
+    let map: HashMap<String, Vec<i32>> = (&v).try_into()?;
+    assert_eq!(
+        map,
+        HashMap::from([("one".into(), vec![1]), ("twothree".into(), vec![2, 3])])
+    );
+    assert_eq!(HashMap::from_bqn(&map.to_bqn()?)?, map);
+    match HashMap::<String, i32>::try_from(&v) {
+        Err(Error::InvalidType(msg)) => assert!(msg.starts_with("at `.")),
+        r => panic!("expected an InvalidType error, got {r:?}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn integer_bounds() -> Result<()> {
+    assert_eq!(i64::try_from(&BQN!("-2⋆63")?)?, i64::MIN);
+    assert_eq!(u64::try_from(&BQN!("2⋆53")?)?, 1 << 53);
+    assert_eq!(i8::try_from(&BQN!("127")?)?, 127);
+    assert_eq!(u8::try_from(&BQN!("255")?)?, 255);
+    // The casts saturate at these values
+    assert!(i64::try_from(&BQN!("2⋆63")?).is_err());
+    assert!(isize::try_from(&BQN!("2⋆63")?).is_err());
+    assert!(u64::try_from(&BQN!("2⋆64")?).is_err());
+    assert!(usize::try_from(&BQN!("2⋆64")?).is_err());
+    assert!(u8::try_from(&BQN!("256")?).is_err());
+
+    assert_eq!((1i64 << 53).to_bqn()?.to_f64()?, 9007199254740992.0);
+    assert_eq!((1u64 << 63).to_bqn()?.to_f64()?, 9223372036854775808.0);
+    assert!(i64::MAX.to_bqn().is_err());
+    assert!(((1u64 << 53) + 1).to_bqn().is_err());
+    assert!(usize::MAX.to_bqn().is_err());
+    Ok(())
+}
+
+#[test]
+fn should_fail_try_into() -> Result<()> {
+    assert!(<(f64, f64)>::try_from(&BQN!("1‿2‿3")?).is_err());
+    assert!(<(f64,)>::try_from(&BQN!("1")?).is_err());
+    assert!(u8::try_from(&BQN!("¯1")?).is_err());
+    assert!(String::try_from(&BQN!("1‿2")?).is_err());
+    assert!(HashMap::<String, f64>::try_from(&BQN!("1‿2")?).is_err());
+    match Vec::<(f64, String)>::try_from(&BQN!("⟨1‿\"a\", 2‿3⟩")?) {
+        Err(Error::InvalidType(msg)) => assert!(msg.starts_with("at `[1][1]`: ")),
+        r => panic!("expected an InvalidType error, got {r:?}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,492 @@
+use crate::namespace::{make_namespace, namespace_fields, normalize_name};
+use crate::{BQNType, BQNValue, Error, Result};
+use std::collections::HashMap;
+
+/// Conversion of Rust values into `BQNValue`s
+///
+/// Implemented for numbers, `bool`, `char`, `String`, `BQNValue`, and for `Vec`s, `Option`s,
+/// tuples and `HashMap<String, T>`s of those. With the `derive` feature, it can be derived for
+/// structs and enums with `#[derive(ToBQN)]`.
+///
+/// `bool`s are represented as `0` and `1`, and `None` as the null character `@`. Tuples are
+/// represented as lists and `HashMap`s as namespaces. Converting an integer that a BQN number
+/// can't represent exactly, such as `i64::MAX`, returns an error.
+///
+/// # Deriving
+///
+/// Derived types are represented as follows:
+///
+/// | Rust                          | BQN                                                     |
+/// |-------------------------------|---------------------------------------------------------|
+/// | structs with named fields     | namespace                                               |
+/// | tuple structs                 | list                                                    |
+/// | newtype structs               | same as the field                                       |
+/// | unit structs                  | null character `@`                                      |
+/// | unit variants                 | name of the variant as a string                         |
+/// | other enum variants           | namespace with the variant as its only field            |
+///
+/// The data of an enum variant is represented like a struct with the same fields. Namespace field
+/// and variant names are normalized the same way as in BQN, so `server_port` is stored as
+/// `serverport`. Names that aren't valid BQN names after the normalization are rejected at compile
+/// time.
+///
+/// The representation can be adjusted with `#[bqn(...)]` attributes:
+///
+/// * `#[bqn(list)]` on a struct or a variant with named fields represents it as a list in field
+///   order. On an enum, it applies to all of its struct variants.
+/// * `#[bqn(namespace)]` on a variant overrides `#[bqn(list)]` of the enum.
+/// * `#[bqn(rename = "name")]` on a field or a variant sets the name used in BQN.
+/// * `#[bqn(skip)]` on a field leaves it out of the BQN value. [`FromBQN`] fills skipped fields
+///   with `Default::default()`.
+///
+/// Conversion errors of derived types are [`Error::InvalidType`]s whose message starts with the
+/// path of the failing value, as in ``at `Config.tags[1]`: value isn't an array``.
+///
+/// ```
+/// # #[cfg(feature = "derive")]
+/// # {
+/// # use cbqn::{BQNValue, eval};
+/// use cbqn::{BQN, FromBQN, ToBQN};
+///
+/// #[derive(ToBQN, FromBQN, Debug, PartialEq)]
+/// struct Config {
+///     name: String,
+///     #[bqn(rename = "port")]
+///     server_port: u16,
+///     #[bqn(skip)]
+///     cache: Vec<u8>,
+/// }
+///
+/// let v = BQN!("{name⇐\"server\" ⋄ port⇐8080}")?;
+/// let config = Config::from_bqn(&v)?;
+/// assert_eq!(config.server_port, 8080);
+/// assert_eq!(BQN!("{𝕩.port+1}", config.to_bqn()?)?.to_f64()?, 8081.0);
+/// # }
+/// # Ok::<(), cbqn::Error>(())
+/// ```
+pub trait ToBQN {
+    /// Converts `self` into a `BQNValue`
+    fn to_bqn(&self) -> Result<BQNValue>;
+}
+
+/// Conversion of `BQNValue`s into Rust values
+///
+/// Implemented for the same types as [`ToBQN`]. With the `derive` feature, it can be derived for
+/// structs and enums with `#[derive(FromBQN)]`, using the representation described in [`ToBQN`].
+/// Namespaces may contain fields that the struct doesn't have.
+///
+/// The types implemented in this crate also implement `TryFrom<&BQNValue>`, so the conversions can
+/// be done with `try_into`. Tuples are converted from lists of the same length, and
+/// `HashMap<String, T>`s from namespaces with the normalized field names as keys.
+///
+/// The trait is also exported as `FromBQNValue`. `FromBQN` is the primary name, as it pairs with
+/// [`ToBQN`] and the derive macros.
+///
+/// # Examples
+/// ```
+/// # use cbqn::{BQN, BQNValue, eval, Error};
+/// use cbqn::FromBQN;
+///
+/// let v = BQN!("⟨1‿\"one\", 2‿\"two\"⟩")?;
+/// let pairs: Vec<(f64, String)> = (&v).try_into()?;
+/// assert_eq!(pairs, vec![(1.0, "one".into()), (2.0, "two".into())]);
+///
+/// let missing = Option::<u8>::from_bqn(&BQN!("@")?)?;
+/// assert_eq!(missing, None);
+/// # Ok::<(), Error>(())
+/// ```
+pub trait FromBQN: Sized {
+    /// Converts `value` into `Self`
+    fn from_bqn(value: &BQNValue) -> Result<Self>;
+}
+
+// `TryFrom<&BQNValue>` can't be implemented for every `T: FromBQN` because of the blanket
+// implementation in the standard library, so it's implemented for each type separately
+macro_rules! impl_try_from {
+    ($($ty:ty),*) => {
+        $(
+            impl TryFrom<&BQNValue> for $ty {
+                type Error = Error;
+
+                fn try_from(value: &BQNValue) -> Result<Self> {
+                    <$ty as FromBQN>::from_bqn(value)
+                }
+            }
+        )*
+    };
+}
+
+// Converts an integer to a BQN number, or returns an error if f64 can't represent it exactly
+pub(crate) fn exact_f64(v: i128) -> Result<f64> {
+    let f = v as f64;
+    if f as i128 == v {
+        Ok(f)
+    } else {
+        Err(Error::InvalidType(format!(
+            "integer {v} can't be represented exactly as a BQN number"
+        )))
+    }
+}
+
+macro_rules! impl_number {
+    ($($ty:ty),*) => {
+        $(
+            impl ToBQN for $ty {
+                fn to_bqn(&self) -> Result<BQNValue> {
+                    BQNValue::try_from_f64(exact_f64(*self as i128)?)
+                }
+            }
+
+            impl FromBQN for $ty {
+                fn from_bqn(value: &BQNValue) -> Result<Self> {
+                    let f = value.to_f64()?;
+                    let v = f as $ty;
+                    // The cast saturates, and for 64-bit types MAX saturates to a value that rounds
+                    // back to `f` at the upper bound, so the range is checked separately.
+                    // MAX as f64 + 1.0 is the exclusive upper bound for every type.
+                    if v as f64 != f || f >= <$ty>::MAX as f64 + 1.0 {
+                        return Err(Error::InvalidType(format!(
+                            "{f} can't be represented as {}",
+                            stringify!($ty)
+                        )));
+                    }
+                    Ok(v)
+                }
+            }
+
+            impl_try_from!($ty);
+        )*
+    };
+}
+
+impl_number!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl ToBQN for f64 {
+    fn to_bqn(&self) -> Result<BQNValue> {
+        BQNValue::try_from_f64(*self)
+    }
+}
+
+impl FromBQN for f64 {
+    fn from_bqn(value: &BQNValue) -> Result<Self> {
+        value.to_f64()
+    }
+}
+
+impl ToBQN for f32 {
+    fn to_bqn(&self) -> Result<BQNValue> {
+        BQNValue::try_from_f64(f64::from(*self))
+    }
+}
+
+impl FromBQN for f32 {
+    fn from_bqn(value: &BQNValue) -> Result<Self> {
+        Ok(value.to_f64()? as f32)
+    }
+}
+
+impl ToBQN for bool {
+    fn to_bqn(&self) -> Result<BQNValue> {
+        BQNValue::try_from_f64(if *self { 1.0 } else { 0.0 })
+    }
+}
+
+impl FromBQN for bool {
+    fn from_bqn(value: &BQNValue) -> Result<Self> {
+        let v = value.to_f64()?;
+        if v == 0.0 {
+            Ok(false)
+        } else if v == 1.0 {
+            Ok(true)
+        } else {
+            Err(Error::InvalidType(format!("{v} isn't a boolean")))
+        }
+    }
+}
+
+impl ToBQN for char {
+    fn to_bqn(&self) -> Result<BQNValue> {
+        BQNValue::try_from_char(*self)
+    }
+}
+
+impl FromBQN for char {
+    fn from_bqn(value: &BQNValue) -> Result<Self> {
+        value
+            .to_char()?
+            .ok_or_else(|| Error::InvalidType("character isn't a Unicode scalar value".into()))
+    }
+}
+
+impl ToBQN for String {
+    fn to_bqn(&self) -> Result<BQNValue> {
+        BQNValue::try_from_str(self)
+    }
+}
+
+impl ToBQN for &str {
+    fn to_bqn(&self) -> Result<BQNValue> {
+        BQNValue::try_from_str(self)
+    }
+}
+
+impl FromBQN for String {
+    fn from_bqn(value: &BQNValue) -> Result<Self> {
+        value.to_string()
+    }
+}
+
+impl_try_from!(f64, f32, bool, char, String);
+
+impl ToBQN for BQNValue {
+    fn to_bqn(&self) -> Result<BQNValue> {
+        self.try_clone()
+    }
+}
+
+impl FromBQN for BQNValue {
+    fn from_bqn(value: &BQNValue) -> Result<Self> {
+        value.try_clone()
+    }
+}
+
+impl<T: ToBQN> ToBQN for Vec<T> {
+    fn to_bqn(&self) -> Result<BQNValue> {
+        BQNValue::try_from_values(self.iter().map(T::to_bqn).collect::<Result<Vec<_>>>()?)
+    }
+}
+
+impl<T: FromBQN> FromBQN for Vec<T> {
+    fn from_bqn(value: &BQNValue) -> Result<Self> {
+        if value.try_bqn_type()? != BQNType::Array {
+            return Err(Error::InvalidType("value isn't an array".into()));
+        }
+        value
+            .to_bqnvalue_vec()?
+            .iter()
+            .enumerate()
+            .map(|(i, v)| T::from_bqn(v).map_err(|e| in_index(e, i)))
+            .collect()
+    }
+}
+
+impl<T: FromBQN> TryFrom<&BQNValue> for Vec<T> {
+    type Error = Error;
+
+    fn try_from(value: &BQNValue) -> Result<Self> {
+        Self::from_bqn(value)
+    }
+}
+
+impl<T: ToBQN> ToBQN for Option<T> {
+    fn to_bqn(&self) -> Result<BQNValue> {
+        match self {
+            Some(v) => v.to_bqn(),
+            None => BQNValue::try_null(),
+        }
+    }
+}
+
+impl<T: FromBQN> FromBQN for Option<T> {
+    fn from_bqn(value: &BQNValue) -> Result<Self> {
+        if value.try_bqn_type()? == BQNType::Character && value.to_u32()? == 0 {
+            Ok(None)
+        } else {
+            T::from_bqn(value).map(Some)
+        }
+    }
+}
+
+impl<T: FromBQN> TryFrom<&BQNValue> for Option<T> {
+    type Error = Error;
+
+    fn try_from(value: &BQNValue) -> Result<Self> {
+        Self::from_bqn(value)
+    }
+}
+
+impl<T: ToBQN> ToBQN for HashMap<String, T> {
+    fn to_bqn(&self) -> Result<BQNValue> {
+        let fields = self
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), v.to_bqn().map_err(|e| in_key(e, k))?)))
+            .collect::<Result<Vec<_>>>()?;
+        make_namespace(fields)
+    }
+}
+
+impl<T: FromBQN> FromBQN for HashMap<String, T> {
+    fn from_bqn(value: &BQNValue) -> Result<Self> {
+        namespace_fields(value)?
+            .into_iter()
+            .map(|(k, v)| {
+                let v = T::from_bqn(&v).map_err(|e| in_key(e, &k))?;
+                Ok((k, v))
+            })
+            .collect()
+    }
+}
+
+impl<T: FromBQN> TryFrom<&BQNValue> for HashMap<String, T> {
+    type Error = Error;
+
+    fn try_from(value: &BQNValue) -> Result<Self> {
+        Self::from_bqn(value)
+    }
+}
+
+// Tuples are represented as lists with one element for each field
+macro_rules! impl_tuple {
+    ($len:literal => $($name:ident $idx:tt),+) => {
+        impl<$($name: ToBQN),+> ToBQN for ($($name,)+) {
+            fn to_bqn(&self) -> Result<BQNValue> {
+                BQNValue::try_from_values(vec![$(self.$idx.to_bqn()?),+])
+            }
+        }
+
+        impl<$($name: FromBQN),+> FromBQN for ($($name,)+) {
+            fn from_bqn(value: &BQNValue) -> Result<Self> {
+                let elems = fixed_list(value, $len)?;
+                Ok(($($name::from_bqn(&elems[$idx]).map_err(|e| in_index(e, $idx))?,)+))
+            }
+        }
+
+        impl<$($name: FromBQN),+> TryFrom<&BQNValue> for ($($name,)+) {
+            type Error = Error;
+
+            fn try_from(value: &BQNValue) -> Result<Self> {
+                Self::from_bqn(value)
+            }
+        }
+    };
+}
+
+impl_tuple!(1 => A 0);
+impl_tuple!(2 => A 0, B 1);
+impl_tuple!(3 => A 0, B 1, C 2);
+impl_tuple!(4 => A 0, B 1, C 2, D 3);
+impl_tuple!(5 => A 0, B 1, C 2, D 3, E 4);
+impl_tuple!(6 => A 0, B 1, C 2, D 3, E 4, F 5);
+impl_tuple!(7 => A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_tuple!(8 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+fn fixed_list(value: &BQNValue, len: usize) -> Result<Vec<BQNValue>> {
+    if value.try_bqn_type()? != BQNType::Array {
+        return Err(Error::InvalidType("value isn't a list".into()));
+    }
+    let elems = value.to_bqnvalue_vec()?;
+    if elems.len() != len {
+        return Err(Error::InvalidType(format!(
+            "expected a list of length {len}, found length {}",
+            elems.len()
+        )));
+    }
+    Ok(elems)
+}
+
+// Errors from nested values carry the path to the failing value in the form "at `path`: msg".
+// The path starts with the name of the innermost type that knows its fields, and each level
+// replaces that with its own type name and field.
+const PATH_PREFIX: &str = "at `";
+
+fn with_path(err: Error, f: impl FnOnce(&str) -> String) -> Error {
+    match err {
+        Error::InvalidType(msg) => match msg
+            .strip_prefix(PATH_PREFIX)
+            .and_then(|m| m.split_once("`: "))
+        {
+            Some((path, msg)) => {
+                // Drop the type name of the inner value
+                let rest = path.find(['.', '[']).map_or("", |i| &path[i..]);
+                Error::InvalidType(format!("{PATH_PREFIX}{}`: {msg}", f(rest)))
+            }
+            None => Error::InvalidType(format!("{PATH_PREFIX}{}`: {msg}", f(""))),
+        },
+        e => e,
+    }
+}
+
+/// Adds a struct field to the path of a conversion error
+#[doc(hidden)]
+pub fn in_field(err: Error, ty: &str, field: &str) -> Error {
+    with_path(err, |rest| format!("{ty}.{field}{rest}"))
+}
+
+/// Adds the type name to the path of a conversion error
+#[doc(hidden)]
+pub fn in_type(err: Error, ty: &str) -> Error {
+    with_path(err, |rest| format!("{ty}{rest}"))
+}
+
+fn in_index(err: Error, i: usize) -> Error {
+    with_path(err, |rest| format!("[{i}]{rest}"))
+}
+
+fn in_key(err: Error, key: &str) -> Error {
+    with_path(err, |rest| format!(".{key}{rest}"))
+}
+
+/// Returns the error for a field missing from a namespace
+#[doc(hidden)]
+pub fn missing_field(ty: &str, field: &str, name: &str) -> Error {
+    Error::InvalidType(format!(
+        "{PATH_PREFIX}{ty}.{field}`: namespace has no field `{name}`"
+    ))
+}
+
+/// Returns the error for an enum value whose variant isn't known
+#[doc(hidden)]
+pub fn unknown_variant(ty: &str, variant: &str) -> Error {
+    Error::InvalidType(format!(
+        "{PATH_PREFIX}{ty}`: unknown variant `{variant}` or invalid variant data"
+    ))
+}
+
+/// Checks that a value representing a unit struct is `@`
+#[doc(hidden)]
+pub fn expect_null(value: &BQNValue, ty: &str) -> Result<()> {
+    if value.try_bqn_type()? == BQNType::Character && value.to_u32()? == 0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidType(format!(
+            "{PATH_PREFIX}{ty}`: expected `@`"
+        )))
+    }
+}
+
+/// Removes a field from a list of namespace fields
+#[doc(hidden)]
+pub fn take_field(fields: &mut Vec<(String, BQNValue)>, name: &str) -> Option<BQNValue> {
+    let i = fields.iter().position(|(n, _)| n == name)?;
+    Some(fields.swap_remove(i).1)
+}
+
+/// Returns the elements of a list that represents a type with `len` fields
+#[doc(hidden)]
+pub fn list_elements(value: &BQNValue, ty: &str, len: usize) -> Result<Vec<BQNValue>> {
+    fixed_list(value, len).map_err(|e| in_type(e, ty))
+}
+
+/// Returns the normalized variant name and the data of an enum value
+///
+/// Unit variants are represented as strings, and variants with data as namespaces that have the
+/// variant as their only field.
+#[doc(hidden)]
+pub fn enum_variant(value: &BQNValue, ty: &str) -> Result<(String, Option<BQNValue>)> {
+    match value.try_bqn_type()? {
+        BQNType::Array => Ok((normalize_name(&value.to_string()?)?, None)),
+        BQNType::Namespace => {
+            let mut fields = namespace_fields(value)?;
+            if fields.len() != 1 {
+                return Err(Error::InvalidType(format!(
+                    "{PATH_PREFIX}{ty}`: expected a namespace with exactly one field"
+                )));
+            }
+            let (name, value) = fields.remove(0);
+            Ok((name, Some(value)))
+        }
+        _ => Err(Error::InvalidType(format!(
+            "{PATH_PREFIX}{ty}`: expected a string or a namespace"
+        ))),
+    }
+}